@@ -0,0 +1,704 @@
+// A small full-screen editor built into the tutor, for machines without a
+// terminal editor (minimal containers, serial consoles). It is used when
+// the editor is set to "builtin", or when no other editor can be found,
+// and edits the learner's code in memory: nothing is written to disk.
+//
+// Keys follow nano and most desktop editors: Ctrl-S saves, Ctrl-Q cancels,
+// Ctrl-Z and Ctrl-Y undo and redo, Ctrl-F searches and F3 searches again.
+
+use crate::style;
+use ratatui::crossterm::event::{self, DisableBracketedPaste, EnableBracketedPaste, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::crossterm::execute;
+use ratatui::layout::{Constraint, Layout, Position};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::Paragraph;
+use ratatui::{DefaultTerminal, Frame};
+use std::io;
+
+const UNDO_LIMIT: usize = 1000;
+const INDENT: &str = "    ";
+const KEYS: &str = "^S Save  ^Q Cancel  ^Z Undo  ^Y Redo  ^F Find  F3 Find next";
+
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern", "false", "fn", "for",
+    "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return", "self", "Self", "static",
+    "struct", "super", "trait", "true", "type", "unsafe", "use", "where", "while",
+];
+
+// Opens `files` (name and text) with the cursor on `line` of the first one.
+// Returns the edited texts, in the same order, or None if the learner
+// cancelled.
+pub fn edit(files: &[(String, String)], line: Option<usize>) -> io::Result<Option<Vec<String>>> {
+    let mut buffers: Vec<Buffer> = files.iter().map(|(name, text)| Buffer::new(name, text)).collect();
+    if let (Some(buffer), Some(line)) = (buffers.first_mut(), line) {
+        buffer.row = line.saturating_sub(1).min(buffer.lines.len() - 1);
+    }
+    let mut editor = Editor {
+        buffers,
+        current: 0,
+        search: None,
+        query: String::new(),
+        found: None,
+        message: None,
+        confirm_cancel: false,
+        color: style::enabled(),
+    };
+
+    let mut terminal = ratatui::try_init()?;
+    let result = execute!(io::stdout(), EnableBracketedPaste).and_then(|()| editor.run(&mut terminal));
+    let _ = execute!(io::stdout(), DisableBracketedPaste);
+    ratatui::restore();
+    result
+}
+
+#[derive(Clone)]
+struct Snapshot {
+    lines: Vec<String>,
+    row: usize,
+    col: usize,
+}
+
+struct Buffer {
+    name: String,
+    lines: Vec<String>,
+    // Cursor line, and column in characters
+    row: usize,
+    col: usize,
+    // First line and column on screen
+    top: usize,
+    left: usize,
+    undo: Vec<Snapshot>,
+    redo: Vec<Snapshot>,
+    // Whether the last change was typing; a run of typing is undone at once
+    typing: bool,
+    modified: bool,
+}
+
+impl Buffer {
+    fn new(name: &str, text: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            lines: text.split('\n').map(str::to_string).collect(),
+            row: 0,
+            col: 0,
+            top: 0,
+            left: 0,
+            undo: Vec::new(),
+            redo: Vec::new(),
+            typing: false,
+            modified: false,
+        }
+    }
+
+    fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+
+    fn line_len(&self) -> usize {
+        self.lines[self.row].chars().count()
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Snapshot { lines: self.lines.clone(), row: self.row, col: self.col }
+    }
+
+    // Call before every change, so it can be undone
+    fn change(&mut self, typing: bool) {
+        if !(typing && self.typing) {
+            self.undo.push(self.snapshot());
+            if self.undo.len() > UNDO_LIMIT {
+                self.undo.remove(0);
+            }
+        }
+        self.redo.clear();
+        self.typing = typing;
+        self.modified = true;
+    }
+
+    fn undo(&mut self) -> bool {
+        let Some(snapshot) = self.undo.pop() else { return false };
+        self.redo.push(self.snapshot());
+        self.restore(snapshot);
+        true
+    }
+
+    fn redo(&mut self) -> bool {
+        let Some(snapshot) = self.redo.pop() else { return false };
+        self.undo.push(self.snapshot());
+        self.restore(snapshot);
+        true
+    }
+
+    fn restore(&mut self, snapshot: Snapshot) {
+        self.lines = snapshot.lines;
+        self.row = snapshot.row;
+        self.col = snapshot.col;
+        self.typing = false;
+        self.modified = true;
+    }
+
+    fn insert(&mut self, text: &str) {
+        for (i, part) in text.split('\n').enumerate() {
+            if i > 0 {
+                self.split_line("");
+            }
+            let at = byte_index(&self.lines[self.row], self.col);
+            self.lines[self.row].insert_str(at, part);
+            self.col += part.chars().count();
+        }
+    }
+
+    // Breaks the line at the cursor, starting the new line with `indent`
+    fn split_line(&mut self, indent: &str) {
+        let at = byte_index(&self.lines[self.row], self.col);
+        let rest = self.lines[self.row].split_off(at);
+        self.row += 1;
+        self.lines.insert(self.row, format!("{}{}", indent, rest));
+        self.col = indent.chars().count();
+    }
+
+    fn newline(&mut self) {
+        let line = &self.lines[self.row];
+        let mut indent: String = line.chars().take_while(|c| *c == ' ' || *c == '\t').collect();
+        if line[..byte_index(line, self.col)].trim_end().ends_with('{') {
+            indent.push_str(INDENT);
+        }
+        self.split_line(&indent);
+    }
+
+    fn backspace(&mut self) {
+        if self.col > 0 {
+            let line = &mut self.lines[self.row];
+            let start = byte_index(line, self.col - 1);
+            line.replace_range(start..byte_index(line, self.col), "");
+            self.col -= 1;
+        } else if self.row > 0 {
+            let line = self.lines.remove(self.row);
+            self.row -= 1;
+            self.col = self.line_len();
+            self.lines[self.row].push_str(&line);
+        }
+    }
+
+    fn delete(&mut self) {
+        if self.col < self.line_len() {
+            let line = &mut self.lines[self.row];
+            let start = byte_index(line, self.col);
+            line.replace_range(start..byte_index(line, self.col + 1), "");
+        } else if self.row + 1 < self.lines.len() {
+            let next = self.lines.remove(self.row + 1);
+            self.lines[self.row].push_str(&next);
+        }
+    }
+
+    fn move_to(&mut self, row: usize, col: usize) {
+        self.row = row.min(self.lines.len() - 1);
+        self.col = col.min(self.line_len());
+        self.typing = false;
+    }
+
+    // The next occurrence of `query` after the cursor, wrapping around
+    fn find(&self, query: &str) -> Option<(usize, usize)> {
+        let count = self.lines.len();
+        (0..=count).find_map(|offset| {
+            let row = (self.row + offset) % count;
+            let line = &self.lines[row];
+            let from = if offset == 0 { byte_index(line, self.col + 1) } else { 0 };
+            let found = line.get(from..)?.find(query)? + from;
+            Some((row, line[..found].chars().count()))
+        })
+    }
+}
+
+struct Editor {
+    buffers: Vec<Buffer>,
+    current: usize,
+    // What has been typed at the search prompt, while it is open
+    search: Option<String>,
+    query: String,
+    // Where the last search match is, to highlight it: line, column, length
+    found: Option<(usize, usize, usize)>,
+    message: Option<String>,
+    // Set after a first Ctrl-Q with unsaved changes
+    confirm_cancel: bool,
+    color: bool,
+}
+
+impl Editor {
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> io::Result<Option<Vec<String>>> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+            let key = match event::read()? {
+                Event::Key(key) if key.kind != KeyEventKind::Release => key,
+                Event::Paste(text) => {
+                    let text = text.replace("\r\n", "\n").replace('\r', "\n");
+                    match &mut self.search {
+                        Some(search) => search.push_str(text.lines().next().unwrap_or_default()),
+                        None => {
+                            let buffer = &mut self.buffers[self.current];
+                            buffer.change(false);
+                            buffer.insert(&text);
+                        }
+                    }
+                    continue;
+                }
+                _ => continue,
+            };
+
+            let cancel = self.confirm_cancel;
+            self.confirm_cancel = false;
+            self.message = None;
+            if self.search.is_some() {
+                self.search_key(key);
+                continue;
+            }
+
+            let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+            let count = self.buffers.len();
+            let buffer = &mut self.buffers[self.current];
+            match key.code {
+                KeyCode::Char('s') if ctrl => return Ok(Some(self.buffers.iter().map(Buffer::text).collect())),
+                KeyCode::Char('q') if ctrl => {
+                    if cancel || !self.buffers.iter().any(|buffer| buffer.modified) {
+                        return Ok(None);
+                    }
+                    self.confirm_cancel = true;
+                    self.message = Some("Your changes are not saved. Press Ctrl-Q again to throw them away, or Ctrl-S to save.".to_string());
+                }
+                KeyCode::Char('z') if ctrl && !buffer.undo() => self.message = Some("Nothing to undo.".to_string()),
+                KeyCode::Char('y') if ctrl && !buffer.redo() => self.message = Some("Nothing to redo.".to_string()),
+                KeyCode::Char('z' | 'y') if ctrl => {}
+                KeyCode::Char('f') if ctrl => self.search = Some(String::new()),
+                KeyCode::F(3) => self.find_next(),
+                KeyCode::Char('n') if ctrl => self.current = (self.current + 1) % count,
+                KeyCode::Home if ctrl => buffer.move_to(0, 0),
+                KeyCode::End if ctrl => buffer.move_to(usize::MAX, usize::MAX),
+                KeyCode::Char(_) if ctrl => {}
+                KeyCode::Char(c) => {
+                    buffer.change(!c.is_whitespace());
+                    buffer.insert(&c.to_string());
+                }
+                KeyCode::Tab => {
+                    buffer.change(false);
+                    buffer.insert(INDENT);
+                }
+                KeyCode::Enter => {
+                    buffer.change(false);
+                    buffer.newline();
+                }
+                KeyCode::Backspace if buffer.col > 0 || buffer.row > 0 => {
+                    buffer.change(false);
+                    buffer.backspace();
+                }
+                KeyCode::Delete if buffer.col < buffer.line_len() || buffer.row + 1 < buffer.lines.len() => {
+                    buffer.change(false);
+                    buffer.delete();
+                }
+                KeyCode::Left if buffer.col > 0 => buffer.move_to(buffer.row, buffer.col - 1),
+                KeyCode::Left if buffer.row > 0 => buffer.move_to(buffer.row - 1, usize::MAX),
+                KeyCode::Right if buffer.col < buffer.line_len() => buffer.move_to(buffer.row, buffer.col + 1),
+                KeyCode::Right if buffer.row + 1 < buffer.lines.len() => buffer.move_to(buffer.row + 1, 0),
+                KeyCode::Up => buffer.move_to(buffer.row.saturating_sub(1), buffer.col),
+                KeyCode::Down => buffer.move_to(buffer.row + 1, buffer.col),
+                KeyCode::PageUp => buffer.move_to(buffer.row.saturating_sub(page_size(terminal)), buffer.col),
+                KeyCode::PageDown => buffer.move_to(buffer.row + page_size(terminal), buffer.col),
+                KeyCode::Home => buffer.move_to(buffer.row, 0),
+                KeyCode::End => buffer.move_to(buffer.row, usize::MAX),
+                KeyCode::Esc => self.found = None,
+                _ => {}
+            }
+        }
+    }
+
+    fn search_key(&mut self, key: KeyEvent) {
+        let Some(search) = &mut self.search else { return };
+        match key.code {
+            KeyCode::Enter => {
+                if !search.is_empty() {
+                    self.query = std::mem::take(search);
+                }
+                self.search = None;
+                self.find_next();
+            }
+            KeyCode::Esc => self.search = None,
+            KeyCode::Backspace => {
+                search.pop();
+            }
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => search.push(c),
+            _ => {}
+        }
+    }
+
+    fn find_next(&mut self) {
+        if self.query.is_empty() {
+            self.search = Some(String::new());
+            return;
+        }
+        let buffer = &mut self.buffers[self.current];
+        match buffer.find(&self.query) {
+            Some((row, col)) => {
+                buffer.move_to(row, col);
+                self.found = Some((row, col, self.query.chars().count()));
+            }
+            None => {
+                self.found = None;
+                self.message = Some(format!("\"{}\" not found.", self.query));
+            }
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [text_area, status_area, keys_area] =
+            Layout::vertical([Constraint::Min(1), Constraint::Length(1), Constraint::Length(1)]).areas(frame.area());
+        let count = self.buffers.len();
+        let buffer = &mut self.buffers[self.current];
+        let gutter = buffer.lines.len().to_string().len().max(3) + 1;
+        let height = text_area.height as usize;
+        let width = (text_area.width as usize).saturating_sub(gutter).max(1);
+
+        // Keep the cursor on screen
+        if buffer.row < buffer.top {
+            buffer.top = buffer.row;
+        } else if buffer.row >= buffer.top + height {
+            buffer.top = buffer.row + 1 - height;
+        }
+        if buffer.col < buffer.left {
+            buffer.left = buffer.col;
+        } else if buffer.col >= buffer.left + width {
+            buffer.left = buffer.col + 1 - width;
+        }
+
+        let mut state = Lexer::default();
+        for line in &buffer.lines[..buffer.top] {
+            state.highlight(line, false);
+        }
+        let dim = Style::new().add_modifier(Modifier::DIM);
+        let lines: Vec<Line> = buffer.lines[buffer.top..]
+            .iter()
+            .take(height)
+            .enumerate()
+            .map(|(i, line)| {
+                let row = buffer.top + i;
+                let mut styles = state.highlight(line, self.color);
+                if let Some((_, col, len)) = self.found.filter(|found| found.0 == row) {
+                    for style in styles.iter_mut().skip(col).take(len) {
+                        *style = style.add_modifier(Modifier::REVERSED);
+                    }
+                }
+                let mut spans = vec![Span::styled(format!("{:>1$} ", row + 1, gutter - 1), dim)];
+                spans.extend(group(line, &styles, buffer.left, width));
+                Line::from(spans)
+            })
+            .collect();
+        frame.render_widget(Paragraph::new(lines), text_area);
+
+        let modified = if buffer.modified { " [modified]" } else { "" };
+        let files = if count > 1 {
+            format!("  (file {} of {}, Ctrl-N for the next)", self.current + 1, count)
+        } else {
+            String::new()
+        };
+        let status = format!(" {}{}{}   Ln {}, Col {}", buffer.name, modified, files, buffer.row + 1, buffer.col + 1);
+        let status = format!("{:<1$}", status, status_area.width as usize);
+        frame.render_widget(Line::styled(status, Style::new().add_modifier(Modifier::REVERSED)), status_area);
+
+        match &self.search {
+            Some(search) => {
+                let prompt = format!("Find: {}", search);
+                frame.set_cursor_position(Position::new(keys_area.x + prompt.chars().count() as u16, keys_area.y));
+                frame.render_widget(Line::raw(prompt), keys_area);
+            }
+            None => {
+                let keys = self.message.clone().unwrap_or_else(|| KEYS.to_string());
+                frame.render_widget(Line::raw(keys), keys_area);
+                let x = text_area.x + (gutter + buffer.col - buffer.left) as u16;
+                let y = text_area.y + (buffer.row - buffer.top) as u16;
+                frame.set_cursor_position(Position::new(x, y));
+            }
+        }
+    }
+}
+
+fn page_size(terminal: &DefaultTerminal) -> usize {
+    terminal.size().map(|size| size.height.saturating_sub(3) as usize).unwrap_or(10).max(1)
+}
+
+// Byte offset of the character at `col`, or the end of the line
+fn byte_index(line: &str, col: usize) -> usize {
+    line.char_indices().nth(col).map_or(line.len(), |(i, _)| i)
+}
+
+// The visible part of a line as spans of equally styled characters
+fn group(line: &str, styles: &[Style], left: usize, width: usize) -> Vec<Span<'static>> {
+    let mut spans: Vec<Span> = Vec::new();
+    let mut text = String::new();
+    let mut current = Style::new();
+    for (c, style) in line.chars().zip(styles).skip(left).take(width) {
+        if *style != current && !text.is_empty() {
+            spans.push(Span::styled(std::mem::take(&mut text), current));
+        }
+        current = *style;
+        // Tabs are shown as a single space, so columns stay one per character
+        text.push(if c == '\t' { ' ' } else { c });
+    }
+    if !text.is_empty() {
+        spans.push(Span::styled(text, current));
+    }
+    spans
+}
+
+// Rust syntax highlighting, one line at a time. Block comments and string
+// literals can span lines, so the state carries over from line to line.
+#[derive(Default)]
+struct Lexer {
+    comment_depth: usize,
+    in_string: bool,
+}
+
+impl Lexer {
+    // One style per character of `line`
+    fn highlight(&mut self, line: &str, color: bool) -> Vec<Style> {
+        let chars: Vec<char> = line.chars().collect();
+        let mut styles = vec![Style::new(); chars.len()];
+        let paint = |styles: &mut [Style], range: std::ops::Range<usize>, fg: Color| {
+            if color {
+                for style in &mut styles[range] {
+                    *style = Style::new().fg(fg);
+                }
+            }
+        };
+        let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+
+        let mut i = 0;
+        while i < chars.len() {
+            let start = i;
+            let next = chars.get(i + 1).copied();
+            if self.comment_depth > 0 {
+                while i < chars.len() && self.comment_depth > 0 {
+                    match (chars[i], chars.get(i + 1)) {
+                        ('*', Some('/')) => {
+                            self.comment_depth -= 1;
+                            i += 2;
+                        }
+                        ('/', Some('*')) => {
+                            self.comment_depth += 1;
+                            i += 2;
+                        }
+                        _ => i += 1,
+                    }
+                }
+                paint(&mut styles, start..i.min(chars.len()), Color::DarkGray);
+            } else if self.in_string {
+                while i < chars.len() && self.in_string {
+                    match chars[i] {
+                        '\\' => i += 2,
+                        '"' => {
+                            self.in_string = false;
+                            i += 1;
+                        }
+                        _ => i += 1,
+                    }
+                }
+                paint(&mut styles, start..i.min(chars.len()), Color::Green);
+            } else if chars[i] == '/' && next == Some('/') {
+                i = chars.len();
+                paint(&mut styles, start..i, Color::DarkGray);
+            } else if chars[i] == '/' && next == Some('*') {
+                self.comment_depth = 1;
+                i += 2;
+                paint(&mut styles, start..i, Color::DarkGray);
+            } else if chars[i] == '"' {
+                self.in_string = true;
+                i += 1;
+                paint(&mut styles, start..i, Color::Green);
+            } else if chars[i] == '\'' && (chars.get(i + 2) == Some(&'\'') || next == Some('\\')) {
+                // A character literal rather than a lifetime
+                i += 1;
+                while i < chars.len() && chars[i] != '\'' {
+                    i += if chars[i] == '\\' { 2 } else { 1 };
+                }
+                i = (i + 1).min(chars.len());
+                paint(&mut styles, start..i, Color::Green);
+            } else if chars[i] == '\'' && next.is_some_and(is_ident) {
+                // A lifetime, e.g. 'a or 'static, styled like a type
+                i += 1;
+                while i < chars.len() && is_ident(chars[i]) {
+                    i += 1;
+                }
+                paint(&mut styles, start..i, Color::Cyan);
+            } else if chars[i].is_ascii_digit() {
+                while i < chars.len() && (is_ident(chars[i]) || chars[i] == '.' && chars.get(i + 1).is_some_and(char::is_ascii_digit)) {
+                    i += 1;
+                }
+                paint(&mut styles, start..i, Color::Magenta);
+            } else if is_ident(chars[i]) {
+                while i < chars.len() && is_ident(chars[i]) {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+                if chars.get(i) == Some(&'!') && chars.get(i + 1) != Some(&'=') {
+                    i += 1;
+                    paint(&mut styles, start..i, Color::Blue);
+                } else if KEYWORDS.contains(&word.as_str()) {
+                    paint(&mut styles, start..i, Color::Yellow);
+                } else if word.starts_with(char::is_uppercase) {
+                    paint(&mut styles, start..i, Color::Cyan);
+                }
+            } else {
+                i += 1;
+            }
+        }
+        styles
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Types `text` the way the key handler does, one character at a time
+    fn type_text(buffer: &mut Buffer, text: &str) {
+        for c in text.chars() {
+            buffer.change(!c.is_whitespace());
+            if c == '\n' {
+                buffer.newline();
+            } else {
+                buffer.insert(&c.to_string());
+            }
+        }
+    }
+
+    #[test]
+    fn insert() {
+        let mut buffer = Buffer::new("main.rs", "fn main() {}");
+        buffer.move_to(0, 11);
+        buffer.insert("println!(\"héllo\");");
+        assert_eq!(buffer.text(), "fn main() {println!(\"héllo\");}");
+        assert_eq!(buffer.col, 29);
+        // Pasted text keeps its own line breaks and indentation
+        buffer.insert("\n  x");
+        assert_eq!(buffer.lines, ["fn main() {println!(\"héllo\");", "  x}"]);
+        assert_eq!((buffer.row, buffer.col), (1, 3));
+    }
+
+    #[test]
+    fn newline_indents() {
+        let mut buffer = Buffer::new("main.rs", "fn main() {}");
+        buffer.move_to(0, 11);
+        type_text(&mut buffer, "\nlet x = 5;\nif x > 1 {\nx;");
+        assert_eq!(buffer.text(), "fn main() {\n    let x = 5;\n    if x > 1 {\n        x;}");
+
+        // In the middle of a line, the rest moves down with the same indent
+        let mut buffer = Buffer::new("main.rs", "\tlet a = 1; let b = 2;");
+        buffer.move_to(0, 11);
+        buffer.newline();
+        assert_eq!(buffer.lines, ["\tlet a = 1;", "\t let b = 2;"]);
+        assert_eq!((buffer.row, buffer.col), (1, 1));
+    }
+
+    #[test]
+    fn backspace_and_delete_join_lines() {
+        let mut buffer = Buffer::new("main.rs", "let café = 1;\n    x");
+        buffer.move_to(1, 0);
+        buffer.backspace();
+        assert_eq!(buffer.lines, ["let café = 1;    x"]);
+        assert_eq!((buffer.row, buffer.col), (0, 13));
+        buffer.move_to(0, 8);
+        buffer.backspace();
+        assert_eq!(buffer.text(), "let caf = 1;    x");
+
+        let mut buffer = Buffer::new("main.rs", "a\nb");
+        buffer.backspace();
+        assert_eq!(buffer.text(), "a\nb");
+        buffer.move_to(0, 1);
+        buffer.delete();
+        assert_eq!(buffer.text(), "ab");
+        buffer.move_to(0, 2);
+        buffer.delete();
+        assert_eq!(buffer.text(), "ab");
+    }
+
+    #[test]
+    fn find() {
+        let mut buffer = Buffer::new("main.rs", "// «x» marks x\nlet x = 1;\nprintln!(\"{}\", x);");
+        assert_eq!(buffer.find("x"), Some((0, 4)));
+        buffer.move_to(0, 4);
+        // Columns are in characters, not bytes
+        assert_eq!(buffer.find("x"), Some((0, 13)));
+        buffer.move_to(2, 15);
+        // Past the last match it wraps around to the top
+        assert_eq!(buffer.find("let"), Some((1, 0)));
+        assert_eq!(buffer.find("x"), Some((0, 4)));
+        assert_eq!(buffer.find("nothing"), None);
+    }
+
+    #[test]
+    fn undo_and_redo() {
+        let mut buffer = Buffer::new("main.rs", "");
+        type_text(&mut buffer, "let x");
+        type_text(&mut buffer, " = 1;");
+        assert_eq!(buffer.text(), "let x = 1;");
+
+        // A run of typing goes at once; a space starts a new one
+        assert!(buffer.undo());
+        assert_eq!(buffer.text(), "let x = ");
+        assert!(buffer.undo());
+        assert_eq!(buffer.text(), "let x =");
+        assert!(buffer.redo());
+        assert!(buffer.redo());
+        assert_eq!(buffer.text(), "let x = 1;");
+        assert!(!buffer.redo());
+
+        // A new change drops what could be redone
+        buffer.undo();
+        type_text(&mut buffer, "2");
+        assert_eq!(buffer.text(), "let x = 2");
+        assert!(!buffer.redo());
+        while buffer.undo() {}
+        assert_eq!(buffer.text(), "");
+        assert_eq!((buffer.row, buffer.col), (0, 0));
+    }
+
+    // The colour of each character, as a letter: ' ' for none
+    fn colors(lexer: &mut Lexer, line: &str) -> String {
+        lexer
+            .highlight(line, true)
+            .iter()
+            .map(|style| match style.fg {
+                None => ' ',
+                Some(Color::DarkGray) => 'c',
+                Some(Color::Green) => 's',
+                Some(Color::Yellow) => 'k',
+                Some(Color::Cyan) => 't',
+                Some(Color::Magenta) => 'n',
+                Some(Color::Blue) => 'm',
+                Some(_) => '?',
+            })
+            .collect()
+    }
+
+    #[test]
+    fn strings_and_comments() {
+        let mut lexer = Lexer::default();
+        assert_eq!(colors(&mut lexer, r#"let s = "a \" // b"; // c"#), "kkk     sssssssssss  cccc");
+        assert_eq!(colors(&mut lexer, r#"let c = '"'; let q = '\'';"#), "kkk     sss  kkk     ssss ");
+        // Block comments nest and strings run on over line ends
+        assert_eq!(colors(&mut lexer, "x /* a /* b"), "  ccccccccc");
+        assert_eq!(lexer.comment_depth, 2);
+        assert_eq!(colors(&mut lexer, "still */ */ 1"), "ccccccccccc n");
+        assert_eq!(colors(&mut lexer, "println!(\"two"), "mmmmmmmm ssss");
+        assert_eq!(colors(&mut lexer, "lines\", 2.5);"), "ssssss  nnn  ");
+        assert_eq!(colors(&mut lexer, "String"), "tttttt");
+    }
+
+    #[test]
+    fn lifetimes() {
+        let mut lexer = Lexer::default();
+        assert_eq!(colors(&mut lexer, "fn f<'a>(s: &'a str) -> &'static str"), "kk   tt      tt          ttttttt    ");
+        assert_eq!(colors(&mut lexer, "let c = 'a';"), "kkk     sss ");
+        // No string starts at a lifetime
+        assert!(!lexer.in_string);
+        assert_eq!(lexer.comment_depth, 0);
+    }
+}
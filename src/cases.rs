@@ -0,0 +1,70 @@
+// Test cases for exercises that read their input: each case runs the
+// program once with its own arguments, stdin and environment, and checks
+// that run's output, exit status and error output.
+
+use crate::exit::ExpectedExit;
+use crate::runner::Input;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Case {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub stdin: String,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+    // Checked with the exercise's matcher
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_output: Option<String>,
+    // Defaults to a successful exit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exit: Option<ExpectedExit>,
+    // Text the program must print to stderr
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_stderr: Option<String>,
+}
+
+impl Case {
+    pub fn input(&self) -> Input {
+        Input { args: self.args.clone(), stdin: self.stdin.clone(), env: self.env.clone() }
+    }
+
+    // e.g. "Case 2: negative numbers"
+    pub fn title(&self, index: usize) -> String {
+        match &self.name {
+            Some(name) => format!("Case {}: {}", index + 1, name),
+            None => format!("Case {}", index + 1),
+        }
+    }
+
+    // One line per kind of input the case gives the program
+    pub fn describe_input(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        if !self.args.is_empty() {
+            lines.push(format!("args:  {}", self.args.iter().map(|arg| quote(arg)).collect::<Vec<_>>().join(" ")));
+        }
+        for (name, value) in &self.env {
+            lines.push(format!("env:   {}={}", name, quote(value)));
+        }
+        if !self.stdin.is_empty() {
+            lines.push(format!("stdin: {:?}", self.stdin));
+        }
+        if lines.is_empty() {
+            lines.push("no input".to_string());
+        }
+        lines
+    }
+}
+
+// Quotes arguments the way a shell would need them
+fn quote(arg: &str) -> String {
+    if !arg.is_empty() && arg.chars().all(|c| c.is_ascii_alphanumeric() || "-_./=:,+".contains(c)) {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    }
+}
@@ -0,0 +1,283 @@
+// Command-line interface. Without a subcommand the tutor is interactive;
+// the subcommands do one thing and exit, for use in scripts:
+//
+//   0  success (for `check`: the exercise passed)
+//   1  the exercise failed, or has no solution
+//   2  bad arguments: unknown exercise, unreadable file, ...
+//
+// `list`, `check` and `progress` can print JSON instead (see report.rs).
+// Then stdout carries nothing but the JSON document; the usual text goes
+// to stderr.
+
+use crate::{content, slug, Tutorial};
+use clap::{Parser, Subcommand, ValueEnum};
+use serde::Serialize;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::os::fd::{AsRawFd, FromRawFd, RawFd};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+pub const FAILED: u8 = 1;
+pub const USAGE: u8 = 2;
+
+#[derive(Parser)]
+#[command(name = "rusttutor", version, about = "An interactive Rust tutorial in your terminal")]
+pub struct Cli {
+    /// Editor to open exercises in, e.g. "code --wait"
+    #[arg(long, global = true, value_name = "COMMAND")]
+    pub editor: Option<String>,
+
+    /// Walk through each exercise step by step: title, description, then the editor
+    #[arg(long)]
+    pub guided: bool,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Work through the exercises in a full-screen view
+    Tui,
+    /// List all chapters and exercises
+    List {
+        #[arg(long, value_enum, default_value_t)]
+        format: Format,
+    },
+    /// Show an exercise's description and starting code
+    Show {
+        /// Exercise to show, e.g. 2.1 or primitives/scalar-types
+        exercise: String,
+    },
+    /// Compile and check code against an exercise
+    Check {
+        /// Exercise to check, e.g. 2.1 or primitives/scalar-types
+        exercise: String,
+        /// Code to check instead of your saved code for the exercise
+        #[arg(long)]
+        file: Option<PathBuf>,
+        #[arg(long, value_enum, default_value_t)]
+        format: Format,
+    },
+    /// Show how many exercises you have passed
+    Progress {
+        #[arg(long, value_enum, default_value_t)]
+        format: Format,
+    },
+    /// Print an exercise's solution
+    Solution {
+        /// Exercise whose solution to print, e.g. 2.1 or primitives/scalar-types
+        exercise: String,
+    },
+    /// Check that every exercise's solution passes it
+    Selftest {
+        /// Also check that no exercise's starting code already passes
+        #[arg(long)]
+        initial: bool,
+    },
+    /// Write the exercises out as a content pack
+    Export {
+        /// Directory to write the pack to
+        dir: PathBuf,
+    },
+    /// Convert Rust by Example into a content pack
+    ImportRbe {
+        /// The book's `src` directory
+        src: PathBuf,
+        /// Where to write the pack [default: the content directory]
+        dest: Option<PathBuf>,
+    },
+}
+
+#[derive(Clone, Copy, Default, PartialEq, ValueEnum)]
+pub enum Format {
+    #[default]
+    Text,
+    Json,
+}
+
+impl Command {
+    // Whether the command compiles code, and so needs rustc
+    pub fn needs_rustc(&self) -> bool {
+        matches!(self, Self::Tui | Self::Check { .. } | Self::Selftest { .. })
+    }
+
+    pub fn format(&self) -> Format {
+        match self {
+            Self::List { format } | Self::Check { format, .. } | Self::Progress { format } => *format,
+            _ => Format::Text,
+        }
+    }
+}
+
+// Points stdout at stderr, so anything printed from here on stays out of
+// the JSON, and returns the original stdout to write the JSON to
+pub fn json_output() -> io::Result<File> {
+    redirect_stdout(libc::STDERR_FILENO)
+}
+
+// Runs `f` with everything it prints thrown away
+pub fn quietly<T>(f: impl FnOnce() -> T) -> io::Result<T> {
+    let null = File::options().write(true).open("/dev/null")?;
+    let stdout = redirect_stdout(null.as_raw_fd())?;
+    let result = f();
+    redirect_stdout(stdout.as_raw_fd())?;
+    Ok(result)
+}
+
+// Runs `f` and returns everything it printed, collected in the file at `path`
+pub fn capture<T>(path: &Path, f: impl FnOnce() -> T) -> io::Result<(T, String)> {
+    let file = File::create(path)?;
+    let stdout = redirect_stdout(file.as_raw_fd())?;
+    let result = f();
+    redirect_stdout(stdout.as_raw_fd())?;
+    let output = fs::read(path)?;
+    let _ = fs::remove_file(path);
+    Ok((result, String::from_utf8_lossy(&output).into_owned()))
+}
+
+// Points stdout at `fd` and returns what stdout was before
+fn redirect_stdout(fd: RawFd) -> io::Result<File> {
+    io::stdout().flush()?;
+    // SAFETY: plain syscalls on open descriptors; the duplicate is owned by
+    // nothing else, so the File may take it.
+    unsafe {
+        let stdout = libc::dup(libc::STDOUT_FILENO);
+        if stdout < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let previous = File::from_raw_fd(stdout);
+        if libc::dup2(fd, libc::STDOUT_FILENO) < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(previous)
+    }
+}
+
+fn write_json(mut out: File, value: &impl Serialize) -> ExitCode {
+    let written = serde_json::to_writer_pretty(&mut out, value)
+        .map_err(io::Error::from)
+        .and_then(|()| writeln!(out));
+    match written {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("❌ Could not write JSON: {}", e);
+            ExitCode::from(FAILED)
+        }
+    }
+}
+
+impl Tutorial {
+    // `json` is where JSON goes, for commands given `--format json`
+    pub(crate) fn run_command(&mut self, command: Command, json: Option<File>) -> ExitCode {
+        match command {
+            Command::List { .. } => match json {
+                Some(out) => write_json(out, &self.listing()),
+                None => {
+                    self.list_chapters();
+                    ExitCode::SUCCESS
+                }
+            },
+            Command::Progress { .. } => match json {
+                Some(out) => write_json(out, &self.progress_report()),
+                None => {
+                    self.print_progress();
+                    ExitCode::SUCCESS
+                }
+            },
+            Command::Show { exercise } => {
+                if let Err(e) = self.select_exercise(&exercise) {
+                    eprintln!("❌ {}", e);
+                    return ExitCode::from(USAGE);
+                }
+                self.print_exercise();
+                self.print_starting_code();
+                ExitCode::SUCCESS
+            }
+            Command::Check { exercise, file, .. } => {
+                if let Err(e) = self.select_exercise(&exercise) {
+                    eprintln!("❌ {}", e);
+                    return ExitCode::from(USAGE);
+                }
+                if let Some(file) = file {
+                    match fs::read_to_string(&file) {
+                        Ok(code) => self.current_code = code,
+                        Err(e) => {
+                            eprintln!("❌ Could not read {}: {}", file.display(), e);
+                            return ExitCode::from(USAGE);
+                        }
+                    }
+                }
+                let passed = self.run_current_exercise();
+                if let Some(out) = json {
+                    let written = write_json(out, &self.report);
+                    if written != ExitCode::SUCCESS {
+                        return written;
+                    }
+                }
+                if passed {
+                    ExitCode::SUCCESS
+                } else {
+                    ExitCode::from(FAILED)
+                }
+            }
+            Command::Solution { exercise } => {
+                if let Err(e) = self.select_exercise(&exercise) {
+                    eprintln!("❌ {}", e);
+                    return ExitCode::from(USAGE);
+                }
+                // Printed bare, so it can be redirected straight into a file
+                match &self.chapters[self.current_chapter].exercises[self.current_exercise].solution {
+                    Some(solution) => {
+                        println!("{}", solution.trim_end());
+                        ExitCode::SUCCESS
+                    }
+                    None => {
+                        eprintln!("❌ No solution available for exercise {}.", exercise);
+                        ExitCode::from(FAILED)
+                    }
+                }
+            }
+            Command::Selftest { initial } => self.selftest(initial),
+            Command::Export { dir } => match content::write_pack(&dir, &self.chapters) {
+                Ok(()) => {
+                    let exercise_count: usize = self.chapters.iter().map(|c| c.exercises.len()).sum();
+                    println!("✅ Exported {} chapters with {} exercises to {}", self.chapters.len(), exercise_count, dir.display());
+                    ExitCode::SUCCESS
+                }
+                Err(e) => {
+                    println!("❌ Could not write content pack to {}: {}", dir.display(), e);
+                    ExitCode::from(FAILED)
+                }
+            },
+            // Handled in main: the full-screen view keeps progress like the
+            // prompt does, and importing needs no tutor at all
+            Command::Tui | Command::ImportRbe { .. } => ExitCode::from(USAGE),
+        }
+    }
+
+    fn print_progress(&self) {
+        let report = self.progress_report();
+        println!("\n📊 Progress: {} of {} exercises completed", report.completed, report.total);
+        for chapter in &report.chapters {
+            let marker = if chapter.completed == chapter.total { " ✅" } else { "" };
+            println!("   Chapter {}: {} ({}/{}){}", chapter.number, chapter.title, chapter.completed, chapter.total, marker);
+        }
+        println!("👉 Current exercise: {}", report.current);
+    }
+
+    // Moves to an exercise given by number or slug, e.g. "2.1" or
+    // "primitives/scalar-types". Unlike `goto` there is no guessing, so
+    // scripts always get the exercise they name.
+    pub(crate) fn select_exercise(&mut self, id: &str) -> Result<(), String> {
+        let Some((chapter, exercise)) = slug::find(&self.chapters, id) else {
+            return Err(format!("There is no exercise {}. Run `rusttutor list` to see them all.", id));
+        };
+
+        self.current_chapter = chapter;
+        self.current_exercise = exercise;
+        self.load_exercise_code();
+        Ok(())
+    }
+}
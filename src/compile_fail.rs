@@ -0,0 +1,167 @@
+// Compile-fail exercises are about code that must *not* compile, such as
+// a use after move. They pass when rustc rejects the code with the errors
+// the exercise expects, and with no others.
+//
+// In a content pack:
+//   [compile_fail]
+//   codes = ["E0382"]
+//   message = "borrow of moved value"   # optional regex
+
+use crate::diagnostics::Diagnostic;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct CompileFail {
+    #[serde(default)]
+    pub codes: Vec<String>,
+    pub message: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum Verdict {
+    Passed,
+    // The code compiled after all
+    Compiled,
+    // Expected codes that were not produced, and errors that were not expected
+    WrongErrors { missing: Vec<String>, unexpected: Vec<String> },
+}
+
+impl CompileFail {
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if !self.codes.is_empty() {
+            parts.push(self.codes.join(", "));
+        }
+        if let Some(message) = &self.message {
+            parts.push(format!("an error matching \"{}\"", message));
+        }
+        if parts.is_empty() {
+            "a compile error".to_string()
+        } else {
+            parts.join(" and ")
+        }
+    }
+
+    pub fn check(&self, compiled: bool, diagnostics: &[Diagnostic]) -> Result<Verdict, String> {
+        if compiled {
+            return Ok(Verdict::Compiled);
+        }
+        let pattern = match &self.message {
+            Some(message) => Some(Regex::new(message).map_err(|e| e.to_string())?),
+            None => None,
+        };
+
+        let errors: Vec<&Diagnostic> = diagnostics.iter().filter(|d| d.is_error()).collect();
+        let expected = |error: &&Diagnostic| {
+            let code_ok = error.code().is_some_and(|code| self.codes.iter().any(|c| c == code));
+            let message_ok = pattern.as_ref().is_some_and(|p| p.is_match(&error.message));
+            code_ok || message_ok || (self.codes.is_empty() && pattern.is_none())
+        };
+
+        let mut missing: Vec<String> = self
+            .codes
+            .iter()
+            .filter(|code| !errors.iter().any(|error| error.code() == Some(code.as_str())))
+            .cloned()
+            .collect();
+        if let Some(pattern) = &pattern {
+            if !errors.iter().any(|error| pattern.is_match(&error.message)) {
+                missing.push(format!("an error matching \"{}\"", pattern));
+            }
+        }
+        let unexpected: Vec<String> = errors
+            .iter()
+            .filter(|error| !expected(error))
+            .map(|error| match error.code() {
+                Some(code) => format!("{}: {}", code, error.message),
+                None => error.message.clone(),
+            })
+            .collect();
+
+        if missing.is_empty() && unexpected.is_empty() {
+            Ok(Verdict::Passed)
+        } else {
+            Ok(Verdict::WrongErrors { missing, unexpected })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostics;
+
+    // `rustc --error-format=json` on a use after move, with the rendered
+    // text and byte offsets left out
+    const MOVED: &str = r#"{"message":"borrow of moved value: `s`","code":{"code":"E0382"},"level":"error","spans":[{"file_name":"mv.rs","line_start":3,"line_end":3,"column_start":13,"column_end":14,"is_primary":false,"label":"value moved here"},{"file_name":"mv.rs","line_start":4,"line_end":4,"column_start":20,"column_end":21,"is_primary":true,"label":"value borrowed here after move"},{"file_name":"mv.rs","line_start":2,"line_end":2,"column_start":9,"column_end":10,"is_primary":false,"label":"move occurs because `s` has type `String`, which does not implement the `Copy` trait"}],"children":[{"message":"consider cloning the value if the performance cost is acceptable","code":null,"level":"help","spans":[{"file_name":"mv.rs","line_start":3,"line_end":3,"column_start":14,"column_end":14,"is_primary":true,"label":null}],"children":[]}]}
+{"message":"unused variable: `t`","code":{"code":"unused_variables"},"level":"warning","spans":[{"file_name":"mv.rs","line_start":3,"line_end":3,"column_start":9,"column_end":10,"is_primary":true,"label":null}],"children":[{"message":"`#[warn(unused_variables)]` (part of `#[warn(unused)]`) on by default","code":null,"level":"note","spans":[],"children":[]},{"message":"if this is intentional, prefix it with an underscore","code":null,"level":"help","spans":[{"file_name":"mv.rs","line_start":3,"line_end":3,"column_start":9,"column_end":10,"is_primary":true,"label":null}],"children":[]}]}
+{"message":"aborting due to 1 previous error; 1 warning emitted","code":null,"level":"error","spans":[],"children":[]}
+{"message":"For more information about this error, try `rustc --explain E0382`.","code":null,"level":"failure-note","spans":[],"children":[]}"#;
+
+    // A type mismatch and a `compile_error!`, which has no code
+    const MISMATCHED: &str = r#"{"message":"not done yet","code":null,"level":"error","spans":[{"file_name":"ty.rs","line_start":3,"line_end":3,"column_start":5,"column_end":35,"is_primary":true,"label":null}],"children":[]}
+{"message":"mismatched types","code":{"code":"E0308"},"level":"error","spans":[{"file_name":"ty.rs","line_start":2,"line_end":2,"column_start":18,"column_end":24,"is_primary":true,"label":"expected `u32`, found `&str`"},{"file_name":"ty.rs","line_start":2,"line_end":2,"column_start":12,"column_end":15,"is_primary":false,"label":"expected due to this"}],"children":[]}
+{"message":"aborting due to 2 previous errors","code":null,"level":"error","spans":[],"children":[]}
+{"message":"For more information about this error, try `rustc --explain E0308`.","code":null,"level":"failure-note","spans":[],"children":[]}"#;
+
+    fn expect(codes: &[&str], message: Option<&str>) -> CompileFail {
+        CompileFail { codes: codes.iter().map(|code| code.to_string()).collect(), message: message.map(str::to_string) }
+    }
+
+    fn wrong_errors(verdict: Verdict) -> (Vec<String>, Vec<String>) {
+        match verdict {
+            Verdict::WrongErrors { missing, unexpected } => (missing, unexpected),
+            verdict => panic!("expected wrong errors, got {:?}", verdict),
+        }
+    }
+
+    #[test]
+    fn expected_error() {
+        let moved = diagnostics::parse(MOVED);
+        assert!(matches!(expect(&["E0382"], None).check(false, &moved), Ok(Verdict::Passed)));
+        assert!(matches!(expect(&[], Some("borrow of moved value")).check(false, &moved), Ok(Verdict::Passed)));
+        assert!(matches!(expect(&["E0382"], Some("moved value: `s`$")).check(false, &moved), Ok(Verdict::Passed)));
+        // The warning and the closing summary don't count as errors
+        assert!(matches!(expect(&[], None).check(false, &moved), Ok(Verdict::Passed)));
+        assert!(matches!(expect(&["E0382"], None).check(true, &[]), Ok(Verdict::Compiled)));
+    }
+
+    #[test]
+    fn missing_code() {
+        let (missing, unexpected) = wrong_errors(expect(&["E0499", "E0382"], None).check(false, &diagnostics::parse(MOVED)).unwrap());
+        assert_eq!(missing, ["E0499"]);
+        assert!(unexpected.is_empty());
+    }
+
+    #[test]
+    fn unexpected_code() {
+        let (missing, unexpected) = wrong_errors(expect(&["E0382"], None).check(false, &diagnostics::parse(MISMATCHED)).unwrap());
+        assert_eq!(missing, ["E0382"]);
+        assert_eq!(unexpected, ["not done yet", "E0308: mismatched types"]);
+
+        // An error matching the message is expected even without its code
+        let verdict = expect(&["E0308"], Some("not done")).check(false, &diagnostics::parse(MISMATCHED));
+        assert!(matches!(verdict, Ok(Verdict::Passed)));
+    }
+
+    #[test]
+    fn message_mismatch() {
+        let (missing, unexpected) =
+            wrong_errors(expect(&["E0382"], Some("cannot borrow")).check(false, &diagnostics::parse(MOVED)).unwrap());
+        assert_eq!(missing, ["an error matching \"cannot borrow\""]);
+        assert!(unexpected.is_empty());
+
+        let (missing, unexpected) = wrong_errors(expect(&[], Some("^mismatched")).check(false, &diagnostics::parse(MISMATCHED)).unwrap());
+        assert!(missing.is_empty());
+        assert_eq!(unexpected, ["not done yet"]);
+
+        assert!(expect(&[], Some("(unclosed")).check(false, &[]).is_err());
+    }
+
+    #[test]
+    fn descriptions() {
+        assert_eq!(expect(&[], None).describe(), "a compile error");
+        assert_eq!(expect(&["E0382", "E0505"], Some("moved")).describe(), "E0382, E0505 and an error matching \"moved\"");
+    }
+}
@@ -0,0 +1,44 @@
+// User settings from $XDG_CONFIG_HOME/rusttutor/config.toml. Every key is
+// optional; a missing file means all defaults.
+
+use crate::paths;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct Config {
+    // Editor command line, e.g. "hx" or "code --wait"
+    pub editor: Option<String>,
+    // Run programs with RUST_BACKTRACE=1, so panics show a backtrace
+    #[serde(default)]
+    pub backtrace: bool,
+    // Walk through each exercise step by step instead of starting at the prompt
+    #[serde(default)]
+    pub guided: bool,
+    // Extra prompt commands, e.g. `c = "run"` or `intro = "goto 1.1"`
+    #[serde(default)]
+    pub aliases: BTreeMap<String, String>,
+}
+
+impl Config {
+    pub fn path() -> PathBuf {
+        paths::config_dir().join("config.toml")
+    }
+
+    pub fn load() -> Self {
+        let path = Self::path();
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(_) => return Self::default(),
+        };
+        match toml::from_str(&text) {
+            Ok(config) => config,
+            Err(e) => {
+                println!("⚠️  Ignoring invalid config file {}: {}", path.display(), e);
+                Self::default()
+            }
+        }
+    }
+}
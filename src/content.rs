@@ -0,0 +1,157 @@
+// Content packs let exercises live outside the binary. A pack is a directory
+// with one subdirectory per chapter:
+//
+//   content/
+//     01-hello-world/
+//       chapter.toml          # title, description
+//       01-basic.toml         # one exercise per file
+//       02-comments.toml
+//
+// Chapters and exercises are ordered by file name, so numeric prefixes
+// control the order they appear in. Without a `slug` key, the name minus
+// that prefix is the chapter's or exercise's slug.
+
+use crate::paths;
+use crate::slug;
+use crate::{Chapter, Exercise};
+use std::env;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+const CHAPTER_FILE: &str = "chapter.toml";
+
+// The content directory is $RUSTTUTOR_CONTENT if set, otherwise the
+// `content` folder inside the data directory.
+pub fn content_dir() -> PathBuf {
+    match env::var_os("RUSTTUTOR_CONTENT").filter(|dir| !dir.is_empty()) {
+        Some(dir) => PathBuf::from(dir),
+        None => paths::data_dir().join("content"),
+    }
+}
+
+// Returns None when there is no usable pack, so the caller can fall back
+// to the built-in chapters.
+pub fn load_chapters() -> Option<Vec<Chapter>> {
+    let dir = content_dir();
+    if !dir.is_dir() {
+        return None;
+    }
+
+    let chapters = match load_pack(&dir) {
+        Ok(chapters) => chapters,
+        Err(e) => {
+            eprintln!("⚠️  Could not load content from {}: {}", dir.display(), e);
+            return None;
+        }
+    };
+
+    if chapters.is_empty() {
+        eprintln!("⚠️  No chapters found in {}, using built-in exercises.", dir.display());
+        return None;
+    }
+    Some(chapters)
+}
+
+pub fn load_pack(dir: &Path) -> Result<Vec<Chapter>, String> {
+    let mut chapters = Vec::new();
+    for chapter_dir in sorted_entries(dir)? {
+        if !chapter_dir.is_dir() {
+            continue;
+        }
+        match load_chapter(&chapter_dir) {
+            Ok(Some(chapter)) => chapters.push(chapter),
+            Ok(None) => {}
+            Err(e) => eprintln!("⚠️  Skipping chapter {}: {}", chapter_dir.display(), e),
+        }
+    }
+    Ok(chapters)
+}
+
+fn load_chapter(dir: &Path) -> Result<Option<Chapter>, String> {
+    let chapter_file = dir.join(CHAPTER_FILE);
+    if !chapter_file.is_file() {
+        return Ok(None);
+    }
+    let mut chapter: Chapter = parse_file(&chapter_file)?;
+    if chapter.slug.is_empty() {
+        chapter.slug = slug::from_file_name(&file_name(dir));
+    }
+
+    for path in sorted_entries(dir)? {
+        if path == chapter_file || path.extension().is_none_or(|ext| ext != "toml") {
+            continue;
+        }
+        match parse_file::<Exercise>(&path) {
+            Ok(mut exercise) => {
+                if let Some(name) = exercise.files.keys().find(|name| !is_source_path(name)) {
+                    eprintln!("⚠️  Skipping exercise {}: '{}' is not a valid file name", path.display(), name);
+                    continue;
+                }
+                if let Some(project) = &mut exercise.cargo {
+                    // Hidden tests and compile-fail checks are built with rustc alone,
+                    // which can't see the project's dependencies
+                    let with = if exercise.tests.is_some() { Some("tests") } else { exercise.compile_fail.as_ref().map(|_| "compile_fail") };
+                    if let Some(with) = with {
+                        eprintln!("⚠️  Skipping exercise {}: `{}` can't be used in a `cargo` exercise", path.display(), with);
+                        continue;
+                    }
+                    project.resolve_vendor(dir);
+                }
+                if exercise.slug.is_empty() {
+                    exercise.slug = slug::from_file_name(&file_name(&path));
+                }
+                chapter.exercises.push(exercise);
+            }
+            Err(e) => eprintln!("⚠️  Skipping exercise {}: {}", path.display(), e),
+        }
+    }
+
+    if chapter.exercises.is_empty() {
+        return Err("chapter has no exercises".to_string());
+    }
+    Ok(Some(chapter))
+}
+
+// Extra files must stay inside the workspace and can't replace main.rs
+fn is_source_path(name: &str) -> bool {
+    let path = Path::new(name);
+    name != "main.rs" && path.components().next().is_some() && path.components().all(|c| matches!(c, Component::Normal(_)))
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default()
+}
+
+fn parse_file<T: serde::de::DeserializeOwned>(path: &Path) -> Result<T, String> {
+    let text = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    toml::from_str(&text).map_err(|e| e.to_string())
+}
+
+fn sorted_entries(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let mut entries = fs::read_dir(dir)
+        .map_err(|e| e.to_string())?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .collect::<Vec<_>>();
+    entries.sort();
+    Ok(entries)
+}
+
+// Writes chapters out in the layout `load_pack` reads back.
+pub fn write_pack(dir: &Path, chapters: &[Chapter]) -> Result<(), String> {
+    for (i, chapter) in chapters.iter().enumerate() {
+        let chapter_dir = dir.join(format!("{:02}-{}", i + 1, slug::slugify(&chapter.title)));
+        fs::create_dir_all(&chapter_dir).map_err(|e| e.to_string())?;
+        write_file(&chapter_dir.join(CHAPTER_FILE), chapter)?;
+
+        for (j, exercise) in chapter.exercises.iter().enumerate() {
+            let name = format!("{:02}-{}.toml", j + 1, slug::slugify(&exercise.title));
+            write_file(&chapter_dir.join(name), exercise)?;
+        }
+    }
+    Ok(())
+}
+
+fn write_file<T: serde::Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    let text = toml::to_string(value).map_err(|e| e.to_string())?;
+    fs::write(path, text).map_err(|e| e.to_string())
+}
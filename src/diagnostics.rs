@@ -0,0 +1,204 @@
+// rustc's machine-readable diagnostics (`--error-format=json`), one JSON
+// object per line of stderr, and a renderer that shows them against the
+// learner's code: errors first, then warnings, each with the offending
+// lines and the spans underlined.
+
+use crate::style::{self, paint, BLUE, BOLD, RED, RESET, YELLOW};
+use serde::Deserialize;
+use std::fmt::Write;
+use std::io;
+use std::path::Path;
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Deserialize)]
+pub struct Diagnostic {
+    pub message: String,
+    pub level: String,
+    pub code: Option<DiagnosticCode>,
+    #[serde(default)]
+    pub spans: Vec<Span>,
+    // Notes and help attached to the diagnostic
+    #[serde(default)]
+    pub children: Vec<Diagnostic>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DiagnosticCode {
+    pub code: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Span {
+    pub file_name: String,
+    pub line_start: usize,
+    pub column_start: usize,
+    pub line_end: usize,
+    pub column_end: usize,
+    pub is_primary: bool,
+    pub label: Option<String>,
+}
+
+impl Diagnostic {
+    // Real errors, not the closing "aborting due to N previous errors"
+    pub fn is_error(&self) -> bool {
+        self.level == "error" && !self.message.starts_with("aborting due to")
+    }
+
+    // Real warnings, not the closing "N warnings emitted"
+    pub fn is_warning(&self) -> bool {
+        self.level == "warning" && !self.message.ends_with("emitted")
+    }
+
+    pub fn code(&self) -> Option<&str> {
+        self.code.as_ref().map(|code| code.code.as_str())
+    }
+}
+
+pub struct Compilation {
+    pub success: bool,
+    pub diagnostics: Vec<Diagnostic>,
+    // Raw stderr, for when rustc fails without producing diagnostics
+    pub stderr: String,
+    pub duration: Duration,
+}
+
+impl Compilation {
+    pub fn errors(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.diagnostics.iter().filter(|d| d.is_error())
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.diagnostics.iter().filter(|d| d.is_warning())
+    }
+}
+
+// Compiles `source` inside `work_dir` with JSON diagnostics. The file is
+// passed relative to `work_dir` so messages mention `main.rs`, not a temp path.
+pub fn rustc(source: &Path, output: &Path, extra_args: &[&str], work_dir: &Path) -> io::Result<Compilation> {
+    let file = source.strip_prefix(work_dir).unwrap_or(source);
+    let start = Instant::now();
+    let output = Command::new("rustc")
+        .args(extra_args)
+        .arg("--error-format=json")
+        .arg(file)
+        .arg("-o")
+        .arg(output)
+        .current_dir(work_dir)
+        .output()?;
+    let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+    Ok(Compilation { success: output.status.success(), diagnostics: parse(&stderr), stderr, duration: start.elapsed() })
+}
+
+// Lines that aren't diagnostics (e.g. a crashing compiler) are skipped.
+pub fn parse(stderr: &str) -> Vec<Diagnostic> {
+    stderr
+        .lines()
+        .filter(|line| line.starts_with('{'))
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+// Errors, then warnings, drawn against `files`: each file's name as rustc
+// reports it, and its text.
+pub fn render(compilation: &Compilation, files: &[(String, &str)]) -> String {
+    let color = style::enabled();
+    let files: Vec<(&str, Vec<&str>)> = files.iter().map(|(name, source)| (name.as_str(), source.lines().collect())).collect();
+    let mut out = String::new();
+    for diagnostic in compilation.errors().chain(compilation.warnings()) {
+        render_one(&mut out, diagnostic, &files, color);
+        out.push('\n');
+    }
+    out
+}
+
+// e.g. "2 errors, 1 warning"
+pub fn summary(compilation: &Compilation) -> String {
+    let errors = compilation.errors().count();
+    let warnings = compilation.warnings().count();
+    let mut parts = Vec::new();
+    if errors > 0 {
+        parts.push(format!("{} error{}", errors, if errors == 1 { "" } else { "s" }));
+    }
+    if warnings > 0 {
+        parts.push(format!("{} warning{}", warnings, if warnings == 1 { "" } else { "s" }));
+    }
+    parts.join(", ")
+}
+
+fn render_one(out: &mut String, diagnostic: &Diagnostic, files: &[(&str, Vec<&str>)], color: bool) {
+    let (icon, level_color) = if diagnostic.is_error() { ("❌", RED) } else { ("⚠️ ", YELLOW) };
+    let code = diagnostic.code().map(|code| format!("[{}]", code)).unwrap_or_default();
+    let _ = writeln!(
+        out,
+        "{} {}{}{}{}{}: {}",
+        icon,
+        paint(color, BOLD),
+        paint(color, level_color),
+        diagnostic.level,
+        code,
+        paint(color, RESET),
+        diagnostic.message
+    );
+
+    // Only spans in the learner's files can be drawn; macro internals and
+    // the standard library are left out. The file with the primary span
+    // comes first.
+    let primary_file = diagnostic.spans.iter().find(|span| span.is_primary).map(|span| span.file_name.as_str());
+    let mut spans: Vec<(usize, &Span)> = diagnostic
+        .spans
+        .iter()
+        .filter_map(|span| files.iter().position(|(name, _)| *name == span.file_name).map(|index| (index, span)))
+        .collect();
+    spans.sort_by_key(|(index, span)| {
+        (Some(span.file_name.as_str()) != primary_file, *index, span.line_start, !span.is_primary, span.column_start)
+    });
+    let gutter = spans.iter().map(|(_, span)| span.line_start.to_string().len()).max().unwrap_or(1);
+    let bar = format!("{}{} |{}", paint(color, BLUE), " ".repeat(gutter), paint(color, RESET));
+    // With a single file there's no need to name it
+    let location = |span: &Span| {
+        if files.len() > 1 {
+            format!("{}, line {}, column {}", span.file_name, span.line_start, span.column_start)
+        } else {
+            format!("line {}, column {}", span.line_start, span.column_start)
+        }
+    };
+
+    if let Some((_, primary)) = spans.iter().find(|(_, span)| span.is_primary).or(spans.first()) {
+        let _ = writeln!(out, "{}{}-->{} {}", " ".repeat(gutter), paint(color, BLUE), paint(color, RESET), location(primary));
+        let _ = writeln!(out, "{}", bar);
+    }
+
+    let mut last_line = None;
+    for &(index, span) in &spans {
+        let Some(text) = files[index].1.get(span.line_start.saturating_sub(1)) else { continue };
+        if last_line.is_some_and(|(last_index, _)| last_index != index) {
+            let _ = writeln!(out, "{}{}:::{} {}", " ".repeat(gutter), paint(color, BLUE), paint(color, RESET), location(span));
+        }
+        if last_line != Some((index, span.line_start)) {
+            let _ = writeln!(out, "{}{:>gutter$} |{} {}", paint(color, BLUE), span.line_start, paint(color, RESET), text);
+            last_line = Some((index, span.line_start));
+        }
+
+        // A span running over several lines is underlined to the end of its first line
+        let line_chars = text.chars().count();
+        let end = if span.line_end > span.line_start { line_chars + 1 } else { span.column_end };
+        let width = end.saturating_sub(span.column_start).max(1);
+        let (marker, marker_color) = if span.is_primary { ('^', level_color) } else { ('-', BLUE) };
+        let label = span.label.as_deref().map(|label| format!(" {}", label)).unwrap_or_default();
+        let _ = writeln!(
+            out,
+            "{} {}{}{}{}{}",
+            bar,
+            " ".repeat(span.column_start.saturating_sub(1)),
+            paint(color, marker_color),
+            marker.to_string().repeat(width),
+            label,
+            paint(color, RESET)
+        );
+    }
+
+    for child in &diagnostic.children {
+        let _ = writeln!(out, "{} {}= {}{}: {}", bar, paint(color, BOLD), child.level, paint(color, RESET), child.message);
+    }
+}
@@ -0,0 +1,279 @@
+// Line- and character-level diff between the expected output and what the
+// learner's program printed, rendered like a unified diff: `-` lines are
+// expected but missing, `+` lines were printed but not expected.
+
+use crate::style::{self, paint, DIM, GREEN, GREEN_BG, RED, RED_BG, RESET};
+use std::fmt::Write;
+
+// Unchanged lines shown around each difference
+const CONTEXT: usize = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Same,
+    Missing,
+    Extra,
+}
+
+#[derive(Debug)]
+struct Line<'a> {
+    op: Op,
+    text: &'a str,
+    // 1-based line number in the expected output (the next one, for extras)
+    expected_line: usize,
+}
+
+// Longest-common-subsequence diff; outputs are small enough for the
+// quadratic table.
+fn diff<T: PartialEq>(a: &[T], b: &[T]) -> Vec<(Op, usize)> {
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] { lcs[i + 1][j + 1] + 1 } else { lcs[i + 1][j].max(lcs[i][j + 1]) };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut ops = Vec::new();
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            ops.push((Op::Same, i));
+            i += 1;
+            j += 1;
+        } else if i < a.len() && (j == b.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            ops.push((Op::Missing, i));
+            i += 1;
+        } else {
+            ops.push((Op::Extra, j));
+            j += 1;
+        }
+    }
+    ops
+}
+
+fn diff_lines<'a>(expected: &'a str, actual: &'a str) -> Vec<Line<'a>> {
+    let expected: Vec<&str> = expected.lines().collect();
+    let actual: Vec<&str> = actual.lines().collect();
+    let mut next_expected = 1;
+    diff(&expected, &actual)
+        .into_iter()
+        .map(|(op, index)| {
+            let line = match op {
+                Op::Same | Op::Missing => Line { op, text: expected[index], expected_line: index + 1 },
+                Op::Extra => Line { op, text: actual[index], expected_line: next_expected },
+            };
+            if op != Op::Extra {
+                next_expected = index + 2;
+            }
+            line
+        })
+        .collect()
+}
+
+pub fn render(expected: &str, actual: &str) -> String {
+    let color = style::enabled();
+    let lines = diff_lines(expected, actual);
+    let mut out = String::new();
+
+    // Lines within CONTEXT of a change are shown; longer unchanged runs collapse
+    let shown: Vec<bool> = (0..lines.len())
+        .map(|i| {
+            let lo = i.saturating_sub(CONTEXT);
+            let hi = (i + CONTEXT + 1).min(lines.len());
+            lines[lo..hi].iter().any(|line| line.op != Op::Same)
+        })
+        .collect();
+
+    let mut i = 0;
+    while i < lines.len() {
+        if !shown[i] {
+            let skipped = shown[i..].iter().take_while(|shown| !**shown).count();
+            let _ = writeln!(out, "{}  ⋮ {} matching line{}{}", paint(color, DIM), skipped, plural(skipped), paint(color, RESET));
+            i += skipped;
+            continue;
+        }
+
+        if lines[i].op == Op::Same {
+            let _ = writeln!(out, "  {}", visible(lines[i].text));
+            i += 1;
+            continue;
+        }
+
+        // A run of missing lines followed by extra ones: pair them up so the
+        // characters that differ inside each line can be highlighted
+        let missing: Vec<&Line> = lines[i..].iter().take_while(|line| line.op == Op::Missing).collect();
+        let extra: Vec<&Line> = lines[i + missing.len()..].iter().take_while(|line| line.op == Op::Extra).collect();
+        for (k, line) in missing.iter().enumerate() {
+            let text = match extra.get(k) {
+                Some(other) => highlight(line.text, other.text, color, RED_BG, RED),
+                None => visible(line.text),
+            };
+            let _ = writeln!(out, "{}- {}{}", paint(color, RED), text, paint(color, RESET));
+        }
+        for (k, line) in extra.iter().enumerate() {
+            let text = match missing.get(k) {
+                Some(other) => highlight(line.text, other.text, color, GREEN_BG, GREEN),
+                None => visible(line.text),
+            };
+            let _ = writeln!(out, "{}+ {}{}", paint(color, GREEN), text, paint(color, RESET));
+        }
+        i += missing.len() + extra.len();
+    }
+    out
+}
+
+// e.g. "line 3 differs, 1 line missing"
+pub fn summary(expected: &str, actual: &str) -> String {
+    let lines = diff_lines(expected, actual);
+    let mut differs = Vec::new();
+    let mut missing = 0;
+    let mut extra = 0;
+
+    let mut i = 0;
+    while i < lines.len() {
+        if lines[i].op == Op::Same {
+            i += 1;
+            continue;
+        }
+        let removed: Vec<usize> = lines[i..].iter().take_while(|l| l.op == Op::Missing).map(|l| l.expected_line).collect();
+        let added = lines[i + removed.len()..].iter().take_while(|l| l.op == Op::Extra).count();
+        let paired = removed.len().min(added);
+        differs.extend_from_slice(&removed[..paired]);
+        missing += removed.len() - paired;
+        extra += added - paired;
+        i += removed.len() + added;
+    }
+
+    let mut parts = Vec::new();
+    match differs.len() {
+        0 => {}
+        1 => parts.push(format!("line {} differs", differs[0])),
+        n if n <= 5 => {
+            let numbers: Vec<String> = differs.iter().map(|n| n.to_string()).collect();
+            parts.push(format!("lines {} differ", numbers.join(", ")));
+        }
+        n => parts.push(format!("{} lines differ", n)),
+    }
+    if missing > 0 {
+        parts.push(format!("{} line{} missing", missing, plural(missing)));
+    }
+    if extra > 0 {
+        parts.push(format!("{} extra line{}", extra, plural(extra)));
+    }
+    if parts.is_empty() {
+        "outputs are identical".to_string()
+    } else {
+        parts.join(", ")
+    }
+}
+
+// Renders `line`, highlighting the characters not shared with `other`.
+fn highlight(line: &str, other: &str, color: bool, marked: &'static str, base: &'static str) -> String {
+    let chars: Vec<char> = line.chars().collect();
+    let other_chars: Vec<char> = other.chars().collect();
+    let mut keep = vec![false; chars.len()];
+    for (op, index) in diff(&chars, &other_chars) {
+        if op == Op::Same {
+            keep[index] = true;
+        }
+    }
+
+    let trailing_from = line.trim_end().chars().count();
+    let mut out = String::new();
+    let mut in_mark = false;
+    for (i, (c, keep)) in chars.iter().zip(keep).enumerate() {
+        if !keep && !in_mark {
+            out.push_str(paint(color, marked));
+        } else if keep && in_mark {
+            out.push_str(paint(color, RESET));
+            out.push_str(paint(color, base));
+        }
+        in_mark = !keep;
+        // Whitespace that differs is the hardest thing to spot, so show it
+        match c {
+            ' ' if in_mark || i >= trailing_from => out.push('·'),
+            '\t' => out.push('→'),
+            c => out.push(*c),
+        }
+    }
+    if in_mark {
+        out.push_str(paint(color, RESET));
+        out.push_str(paint(color, base));
+    }
+    out
+}
+
+// Tabs and trailing spaces made visible.
+fn visible(line: &str) -> String {
+    let trimmed = line.trim_end();
+    let mut out = trimmed.replace('\t', "→");
+    for c in line[trimmed.len()..].chars() {
+        out.push(if c == '\t' { '→' } else { '·' });
+    }
+    out
+}
+
+fn plural(n: usize) -> &'static str {
+    if n == 1 { "" } else { "s" }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The "Scalar Types" exercise, and a learner's program that printed the
+    // floats with `{:?}` and forgot the last line
+    const EXPECTED: &str = "logical: true\na_float: 1\nan_integer: 5\ndefault_float: 3\ndefault_integer: 7\ninferred_type: 4294967296\nmutable: 21\nmutable: true";
+    const ACTUAL: &str = "logical: true\na_float: 1.0\nan_integer: 5\ndefault_float: 3.0\ndefault_integer: 7\ninferred_type: 4294967296\nmutable: 21\n";
+
+    fn ops<'a>(lines: &[Line<'a>]) -> Vec<(Op, &'a str, usize)> {
+        lines.iter().map(|line| (line.op, line.text, line.expected_line)).collect()
+    }
+
+    #[test]
+    fn lines() {
+        assert_eq!(
+            ops(&diff_lines(EXPECTED, ACTUAL)),
+            [
+                (Op::Same, "logical: true", 1),
+                (Op::Missing, "a_float: 1", 2),
+                (Op::Extra, "a_float: 1.0", 3),
+                (Op::Same, "an_integer: 5", 3),
+                (Op::Missing, "default_float: 3", 4),
+                (Op::Extra, "default_float: 3.0", 5),
+                (Op::Same, "default_integer: 7", 5),
+                (Op::Same, "inferred_type: 4294967296", 6),
+                (Op::Same, "mutable: 21", 7),
+                (Op::Missing, "mutable: true", 8),
+            ]
+        );
+    }
+
+    #[test]
+    fn extra_lines_point_at_the_next_expected_line() {
+        assert_eq!(
+            ops(&diff_lines("a\nb", "x\na\nb\ny")),
+            [(Op::Extra, "x", 1), (Op::Same, "a", 1), (Op::Same, "b", 2), (Op::Extra, "y", 3)]
+        );
+    }
+
+    #[test]
+    fn summaries() {
+        assert_eq!(summary(EXPECTED, ACTUAL), "lines 2, 4 differ, 1 line missing");
+        assert_eq!(summary("Hello World!", "Hello World!\nI'm a Rustacean!"), "1 extra line");
+        assert_eq!(summary("Hello World!", "Hello world!"), "line 1 differs");
+        assert_eq!(summary("a\nb", "a\nb\n"), "outputs are identical");
+        let numbers = (1..=7).map(|n| n.to_string()).collect::<Vec<_>>().join("\n");
+        assert_eq!(summary(&numbers, "x\nx\nx\nx\nx\nx\nx"), "7 lines differ");
+        assert_eq!(summary("a\nb\nc", ""), "3 lines missing");
+    }
+
+    #[test]
+    fn highlighted_characters() {
+        assert_eq!(highlight("a_float: 1.0", "a_float: 1", false, RED_BG, RED), "a_float: 1.0");
+        assert_eq!(highlight("a_float: 1.0", "a_float: 1", true, RED_BG, RED), format!("a_float: 1{}.0{}{}", RED_BG, RESET, RED));
+        // A space that differs shows up even without color
+        assert_eq!(highlight("x:  1", "x: 1", false, RED_BG, RED), "x: ·1");
+        assert_eq!(visible("tab\there  "), "tab→here··");
+    }
+}
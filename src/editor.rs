@@ -0,0 +1,169 @@
+// Works out which editor to open exercises in, and how to ask it to jump
+// to a particular line.
+//
+// The first of these that is set wins: the --editor flag, `editor` in the
+// config file, $VISUAL, $EDITOR. Otherwise the first common terminal
+// editor found on PATH is used, and failing that the tutor's built-in one.
+
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const FALLBACKS: &[&str] = &["nvim", "vim", "nano", "vi"];
+
+// Selects the built-in editor, e.g. `--editor builtin`
+const BUILTIN: &str = "builtin";
+
+#[derive(Debug, Clone)]
+pub struct Editor {
+    program: String,
+    args: Vec<String>,
+}
+
+impl Editor {
+    pub fn resolve(flag: Option<&str>, configured: Option<&str>) -> Self {
+        let explicit = [flag.map(str::to_string), configured.map(str::to_string), env::var("VISUAL").ok(), env::var("EDITOR").ok()];
+        if let Some(editor) = explicit.into_iter().flatten().find_map(|cmd| Self::parse(&cmd)) {
+            return editor;
+        }
+        FALLBACKS
+            .iter()
+            .find(|program| on_path(program))
+            .and_then(|program| Self::parse(program))
+            .unwrap_or_else(|| Self { program: BUILTIN.to_string(), args: Vec::new() })
+    }
+
+    // Splits a command line such as `code --wait` or `"my editor" -f`.
+    pub fn parse(command: &str) -> Option<Self> {
+        let mut words = split_words(command).into_iter();
+        let program = words.next()?;
+        Some(Self { program, args: words.collect() })
+    }
+
+    pub fn name(&self) -> &str {
+        Path::new(&self.program).file_name().and_then(|name| name.to_str()).unwrap_or(&self.program)
+    }
+
+    pub fn is_builtin(&self) -> bool {
+        self.program == BUILTIN
+    }
+
+    pub fn is_vi_like(&self) -> bool {
+        matches!(self.name(), "vi" | "vim" | "nvim" | "gvim" | "mvim")
+    }
+
+    // Builds the command to open `file`, at `line` (1-based) when the editor
+    // has a known syntax for it.
+    pub fn command(&self, file: &Path, line: Option<usize>) -> Command {
+        let mut command = Command::new(&self.program);
+        command.args(&self.args);
+        let file = file.display().to_string();
+        match (line, self.name()) {
+            (Some(line), "vi" | "vim" | "nvim" | "gvim" | "mvim" | "nano" | "emacs" | "emacsclient" | "micro" | "kak" | "joe" | "ne" | "mg") => {
+                command.arg(format!("+{}", line)).arg(file);
+            }
+            (Some(line), "code" | "codium" | "code-insiders") => {
+                command.arg("--goto").arg(format!("{}:{}", file, line));
+            }
+            (Some(line), "hx" | "helix" | "subl" | "zed") => {
+                command.arg(format!("{}:{}", file, line));
+            }
+            _ => {
+                command.arg(file);
+            }
+        }
+        command
+    }
+
+    // Builds the command to open several files at once, in tabs where the
+    // editor has them.
+    pub fn command_all(&self, files: &[PathBuf]) -> Command {
+        let mut command = Command::new(&self.program);
+        command.args(&self.args);
+        if self.is_vi_like() {
+            command.arg("-p");
+        }
+        command.args(files);
+        command
+    }
+}
+
+fn on_path(program: &str) -> bool {
+    env::var_os("PATH").is_some_and(|paths| env::split_paths(&paths).any(|dir| dir.join(program).is_file()))
+}
+
+// Whitespace-separated words, with single quotes, double quotes and
+// backslash escapes handled the way a shell would.
+fn split_words(command: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut quote = None;
+    let mut chars = command.chars();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some('"'), '\\') | (None, '\\') => {
+                if let Some(escaped) = chars.next() {
+                    word.push(escaped);
+                }
+                in_word = true;
+            }
+            (Some(_), c) => word.push(c),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                in_word = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            (None, c) => {
+                word.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if in_word {
+        words.push(word);
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn words() {
+        assert_eq!(split_words("code --wait"), ["code", "--wait"]);
+        assert_eq!(split_words("  nvim   -u NONE  "), ["nvim", "-u", "NONE"]);
+        assert!(split_words("   ").is_empty());
+    }
+
+    #[test]
+    fn quotes_and_escapes() {
+        assert_eq!(split_words(r#""/Applications/My Editor" -f"#), ["/Applications/My Editor", "-f"]);
+        assert_eq!(split_words(r"'it\s' literal"), [r"it\s", "literal"]);
+        assert_eq!(split_words(r#"say "a \"b\" c""#), ["say", r#"a "b" c"#]);
+        assert_eq!(split_words(r"my\ editor --flag"), ["my editor", "--flag"]);
+        assert_eq!(split_words(r#"emacs -e '' """#), ["emacs", "-e", "", ""]);
+        assert_eq!(split_words(r#"pre"fix"ed"#), ["prefixed"]);
+    }
+
+    #[test]
+    fn command_lines() {
+        let editor = Editor::parse("\"/opt/my vim/nvim\" -u NONE").unwrap();
+        assert_eq!(editor.name(), "nvim");
+        assert!(editor.is_vi_like());
+        let args: Vec<String> = editor
+            .command(Path::new("main.rs"), Some(3))
+            .get_args()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(args, ["-u", "NONE", "+3", "main.rs"]);
+        assert!(Editor::parse("").is_none());
+    }
+}
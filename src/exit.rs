@@ -0,0 +1,233 @@
+// How a program is expected to finish, and what a panic looked like.
+//
+// In exercise files the expected exit is `"success"`, `"panic"` or an exit
+// code. Rust programs that panic exit with code 101 and describe the panic
+// on stderr, which is where the message and backtrace are read from.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::os::unix::process::ExitStatusExt;
+use std::process::ExitStatus;
+
+const PANIC_EXIT_CODE: i32 = 101;
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(try_from = "RawExit", into = "RawExit")]
+pub enum ExpectedExit {
+    #[default]
+    Success,
+    Panic,
+    Code(i32),
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(untagged)]
+enum RawExit {
+    Code(i32),
+    Name(String),
+}
+
+impl TryFrom<RawExit> for ExpectedExit {
+    type Error = String;
+
+    fn try_from(raw: RawExit) -> Result<Self, String> {
+        match raw {
+            RawExit::Code(code) => Ok(Self::Code(code)),
+            RawExit::Name(name) => match name.as_str() {
+                "success" => Ok(Self::Success),
+                "panic" => Ok(Self::Panic),
+                _ => Err(format!("expected \"success\", \"panic\" or an exit code, found \"{}\"", name)),
+            },
+        }
+    }
+}
+
+impl From<ExpectedExit> for RawExit {
+    fn from(exit: ExpectedExit) -> Self {
+        match exit {
+            ExpectedExit::Success => Self::Name("success".to_string()),
+            ExpectedExit::Panic => Self::Name("panic".to_string()),
+            ExpectedExit::Code(code) => Self::Code(code),
+        }
+    }
+}
+
+impl ExpectedExit {
+    pub fn is_success(&self) -> bool {
+        *self == Self::Success
+    }
+
+    pub fn matches(&self, status: ExitStatus, panic: Option<&Panic>) -> bool {
+        match self {
+            Self::Success => status.success(),
+            Self::Panic => panic.is_some(),
+            Self::Code(code) => status.code() == Some(*code),
+        }
+    }
+}
+
+impl fmt::Display for ExpectedExit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Success => write!(f, "finish successfully"),
+            Self::Panic => write!(f, "panic"),
+            Self::Code(code) => write!(f, "exit with code {}", code),
+        }
+    }
+}
+
+// e.g. "exited with code 3" or "was killed by signal 11"
+pub fn describe(status: ExitStatus) -> String {
+    match (status.code(), status.signal()) {
+        (Some(0), _) => "finished successfully".to_string(),
+        (Some(code), _) => format!("exited with code {}", code),
+        (None, Some(signal)) => format!("was killed by signal {}", signal),
+        (None, None) => status.to_string(),
+    }
+}
+
+// The program's own error output, without the panic report that follows it
+pub fn before_panic(stderr: &str) -> &str {
+    let mut offset = 0;
+    for line in stderr.split_inclusive('\n') {
+        if line.starts_with("thread '") && line.contains("panicked at") {
+            return &stderr[..offset];
+        }
+        offset += line.len();
+    }
+    stderr
+}
+
+#[derive(Debug)]
+pub struct Panic {
+    // e.g. "main.rs:4:5"
+    pub location: Option<String>,
+    pub message: String,
+    // Only there when the program ran with RUST_BACKTRACE set
+    pub backtrace: Option<String>,
+}
+
+// Finds the panic report in a program's stderr, e.g.
+//
+//   thread 'main' panicked at main.rs:4:5:
+//   index out of bounds: the len is 3 but the index is 5
+//   note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace
+pub fn parse_panic(status: ExitStatus, stderr: &str) -> Option<Panic> {
+    if status.code() != Some(PANIC_EXIT_CODE) {
+        return None;
+    }
+    let mut lines = stderr.lines().skip_while(|line| !(line.starts_with("thread '") && line.contains("panicked at")));
+    let header = lines.next()?;
+    let location = header
+        .split_once("panicked at ")
+        .map(|(_, location)| location.trim_end_matches(':').to_string())
+        .filter(|location| !location.is_empty());
+
+    let mut message = Vec::new();
+    let mut backtrace = Vec::new();
+    for line in lines {
+        if line.starts_with("note: ") {
+            if !backtrace.is_empty() {
+                break;
+            }
+            continue;
+        }
+        if line == "stack backtrace:" || !backtrace.is_empty() {
+            backtrace.push(line);
+        } else {
+            message.push(line);
+        }
+    }
+
+    Some(Panic {
+        location,
+        message: message.join("\n"),
+        backtrace: (!backtrace.is_empty()).then(|| backtrace[1..].join("\n")),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // stderr of a program that prints a line and then indexes past the end
+    // of a three-element Vec
+    const PANIC: &str = r#"looking up index 5
+
+thread 'main' (30797) panicked at p.rs:5:21:
+index out of bounds: the len is 3 but the index is 5
+note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace
+"#;
+
+    // The same program run with RUST_BACKTRACE=1
+    const PANIC_WITH_BACKTRACE: &str = r#"looking up index 5
+
+thread 'main' (30798) panicked at p.rs:5:21:
+index out of bounds: the len is 3 but the index is 5
+stack backtrace:
+   0: __rustc::rust_begin_unwind
+             at /rustc/59807616e1fa2540724bfbac14d7976d7e4a3860/library/std/src/panicking.rs:689:5
+   1: core::panicking::panic_fmt
+             at /rustc/59807616e1fa2540724bfbac14d7976d7e4a3860/library/core/src/panicking.rs:80:14
+   2: core::panicking::panic_bounds_check
+             at /rustc/59807616e1fa2540724bfbac14d7976d7e4a3860/library/core/src/panicking.rs:271:5
+   3: <usize as core::slice::index::SliceIndex<[T]>>::index
+   4: <alloc::vec::Vec<T,A> as core::ops::index::Index<I>>::index
+   5: p::main
+   6: core::ops::function::FnOnce::call_once
+note: Some details are omitted, run with `RUST_BACKTRACE=full` for a verbose backtrace.
+"#;
+
+    fn exited(code: i32) -> ExitStatus {
+        ExitStatus::from_raw(code << 8)
+    }
+
+    #[test]
+    fn panic_without_backtrace() {
+        let panic = parse_panic(exited(101), PANIC).unwrap();
+        assert_eq!(panic.location.as_deref(), Some("p.rs:5:21"));
+        assert_eq!(panic.message, "index out of bounds: the len is 3 but the index is 5");
+        assert!(panic.backtrace.is_none());
+    }
+
+    #[test]
+    fn panic_with_backtrace() {
+        let panic = parse_panic(exited(101), PANIC_WITH_BACKTRACE).unwrap();
+        assert_eq!(panic.location.as_deref(), Some("p.rs:5:21"));
+        assert_eq!(panic.message, "index out of bounds: the len is 3 but the index is 5");
+        let backtrace = panic.backtrace.unwrap();
+        assert!(backtrace.starts_with("   0: __rustc::rust_begin_unwind\n"));
+        assert!(backtrace.ends_with("   5: p::main\n   6: core::ops::function::FnOnce::call_once"));
+    }
+
+    #[test]
+    fn multi_line_message() {
+        let stderr = "\nthread 'main' (4242) panicked at main.rs:3:5:\nassertion `left == right` failed\n  left: 1\n right: 2\nnote: run with `RUST_BACKTRACE=1` environment variable to display a backtrace\n";
+        let panic = parse_panic(exited(101), stderr).unwrap();
+        assert_eq!(panic.location.as_deref(), Some("main.rs:3:5"));
+        assert_eq!(panic.message, "assertion `left == right` failed\n  left: 1\n right: 2");
+    }
+
+    #[test]
+    fn not_a_panic() {
+        assert!(parse_panic(exited(1), PANIC).is_none());
+        assert!(parse_panic(exited(101), "error: custom exit code\n").is_none());
+    }
+
+    #[test]
+    fn output_before_the_panic() {
+        assert_eq!(before_panic(PANIC), "looking up index 5\n\n");
+        assert_eq!(before_panic(PANIC_WITH_BACKTRACE), "looking up index 5\n\n");
+        assert_eq!(before_panic("no panic here\n"), "no panic here\n");
+    }
+
+    #[test]
+    fn expected_exits() {
+        let panic = parse_panic(exited(101), PANIC);
+        assert!(ExpectedExit::Panic.matches(exited(101), panic.as_ref()));
+        assert!(!ExpectedExit::Success.matches(exited(101), panic.as_ref()));
+        assert!(ExpectedExit::Code(3).matches(exited(3), None));
+        assert_eq!(describe(exited(3)), "exited with code 3");
+        assert_eq!(describe(ExitStatus::from_raw(9)), "was killed by signal 9");
+    }
+}
@@ -0,0 +1,211 @@
+// Test-graded exercises: the exercise's hidden tests are appended to the
+// learner's code, built with `rustc --test`, and the libtest report is
+// parsed into per-test results.
+
+// Module the hidden tests are wrapped in; its name is stripped from reports.
+const TEST_MODULE: &str = "rusttutor_tests";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TestStatus {
+    Passed,
+    Failed,
+    Ignored,
+}
+
+#[derive(Debug)]
+pub struct TestResult {
+    pub name: String,
+    pub status: TestStatus,
+    // Panic message of a failed test
+    pub message: Option<String>,
+}
+
+// The learner's code with the hidden tests appended in their own module.
+pub fn with_tests(code: &str, tests: &str) -> String {
+    format!(
+        "{}\n\n#[cfg(test)]\nmod {} {{\n#[allow(unused_imports)]\nuse super::*;\n\n{}\n}}\n",
+        code.trim_end(),
+        TEST_MODULE,
+        tests.trim_end()
+    )
+}
+
+pub fn parse(output: &str) -> Vec<TestResult> {
+    let mut results = Vec::new();
+    for line in output.lines() {
+        let Some(rest) = line.strip_prefix("test ") else { continue };
+        let Some((name, status)) = rest.rsplit_once(" ... ") else { continue };
+        let status = match status.trim() {
+            "ok" => TestStatus::Passed,
+            "FAILED" => TestStatus::Failed,
+            s if s.starts_with("ignored") => TestStatus::Ignored,
+            _ => continue,
+        };
+        results.push(TestResult { name: name.trim().to_string(), status, message: None });
+    }
+
+    for result in results.iter_mut().filter(|r| r.status == TestStatus::Failed) {
+        result.message = failure_message(output, &result.name);
+    }
+    for result in &mut results {
+        if let Some(short) = result.name.strip_prefix(&format!("{}::", TEST_MODULE)) {
+            result.name = short.to_string();
+        }
+    }
+    results
+}
+
+// The captured output of one failed test, minus libtest's own boilerplate.
+fn failure_message(output: &str, name: &str) -> Option<String> {
+    let header = format!("---- {} stdout ----", name);
+    let section = output.split_once(&header)?.1;
+    let message: Vec<&str> = section
+        .lines()
+        .take_while(|line| !line.starts_with("---- ") && *line != "failures:" && *line != "stack backtrace:")
+        .filter(|line| !line.trim().is_empty())
+        .filter(|line| !(line.starts_with("thread '") && line.contains("panicked at")))
+        .filter(|line| !line.starts_with("note: run with `RUST_BACKTRACE=1`"))
+        .collect();
+    if message.is_empty() {
+        None
+    } else {
+        Some(message.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Output of a `rustc --test` binary for an `add` that subtracts, with
+    // one test ignored and one that prints before it fails
+    const OUTPUT: &str = r#"
+running 4 tests
+test rusttutor_tests::adds_big_numbers ... ignored, slow
+test rusttutor_tests::adds_small_numbers ... FAILED
+test rusttutor_tests::adds_zero ... ok
+test rusttutor_tests::prints_then_panics ... FAILED
+
+failures:
+
+---- rusttutor_tests::adds_small_numbers stdout ----
+
+thread 'rusttutor_tests::adds_small_numbers' (30134) panicked at main.rs:11:27:
+assertion `left == right` failed
+  left: 0
+ right: 4
+note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace
+
+---- rusttutor_tests::prints_then_panics stdout ----
+checking negatives
+
+thread 'rusttutor_tests::prints_then_panics' (30136) panicked at main.rs:21:59:
+add(-1, -1) should be -2
+
+
+failures:
+    rusttutor_tests::adds_small_numbers
+    rusttutor_tests::prints_then_panics
+
+test result: FAILED. 1 passed; 2 failed; 1 ignored; 0 measured; 0 filtered out; finished in 0.00s
+
+"#;
+
+    // The same run with RUST_BACKTRACE=1 and --test-threads=1
+    const OUTPUT_WITH_BACKTRACE: &str = r#"
+running 4 tests
+test rusttutor_tests::adds_big_numbers ... ignored, slow
+test rusttutor_tests::adds_small_numbers ... FAILED
+test rusttutor_tests::adds_zero ... ok
+test rusttutor_tests::prints_then_panics ... FAILED
+
+failures:
+
+---- rusttutor_tests::adds_small_numbers stdout ----
+
+thread 'rusttutor_tests::adds_small_numbers' (30119) panicked at main.rs:11:27:
+assertion `left == right` failed
+  left: 0
+ right: 4
+stack backtrace:
+   0: __rustc::rust_begin_unwind
+             at /rustc/59807616e1fa2540724bfbac14d7976d7e4a3860/library/std/src/panicking.rs:689:5
+   1: core::panicking::panic_fmt
+             at /rustc/59807616e1fa2540724bfbac14d7976d7e4a3860/library/core/src/panicking.rs:80:14
+   2: core::panicking::assert_failed_inner
+             at /rustc/59807616e1fa2540724bfbac14d7976d7e4a3860/library/core/src/panicking.rs:439:17
+   3: core::panicking::assert_failed::<i32, i32>
+             at /rustc/59807616e1fa2540724bfbac14d7976d7e4a3860/library/core/src/panicking.rs:394:5
+   4: main::rusttutor_tests::adds_small_numbers
+   5: main::rusttutor_tests::adds_small_numbers::{{closure}}
+   6: core::ops::function::FnOnce::call_once
+   7: <fn() -> core::result::Result<(), alloc::string::String> as core::ops::function::FnOnce<()>>::call_once
+             at /rustc/59807616e1fa2540724bfbac14d7976d7e4a3860/library/core/src/ops/function.rs:250:5
+note: Some details are omitted, run with `RUST_BACKTRACE=full` for a verbose backtrace.
+
+---- rusttutor_tests::prints_then_panics stdout ----
+checking negatives
+
+thread 'rusttutor_tests::prints_then_panics' (30121) panicked at main.rs:21:59:
+add(-1, -1) should be -2
+stack backtrace:
+   0: std::panicking::begin_panic::<&str>
+             at /rustc/59807616e1fa2540724bfbac14d7976d7e4a3860/library/std/src/panicking.rs:761:5
+   1: main::rusttutor_tests::prints_then_panics
+   2: main::rusttutor_tests::prints_then_panics::{{closure}}
+   3: core::ops::function::FnOnce::call_once
+   4: <fn() -> core::result::Result<(), alloc::string::String> as core::ops::function::FnOnce<()>>::call_once
+             at /rustc/59807616e1fa2540724bfbac14d7976d7e4a3860/library/core/src/ops/function.rs:250:5
+note: Some details are omitted, run with `RUST_BACKTRACE=full` for a verbose backtrace.
+
+
+failures:
+    rusttutor_tests::adds_small_numbers
+    rusttutor_tests::prints_then_panics
+
+test result: FAILED. 1 passed; 2 failed; 1 ignored; 0 measured; 0 filtered out; finished in 0.01s
+
+"#;
+
+    #[test]
+    fn results() {
+        let results = parse(OUTPUT);
+        let statuses: Vec<(&str, TestStatus)> = results.iter().map(|r| (r.name.as_str(), r.status)).collect();
+        assert_eq!(
+            statuses,
+            [
+                ("adds_big_numbers", TestStatus::Ignored),
+                ("adds_small_numbers", TestStatus::Failed),
+                ("adds_zero", TestStatus::Passed),
+                ("prints_then_panics", TestStatus::Failed),
+            ]
+        );
+        assert_eq!(results[1].message.as_deref(), Some("assertion `left == right` failed\n  left: 0\n right: 4"));
+        assert_eq!(results[2].message, None);
+        assert_eq!(results[3].message.as_deref(), Some("checking negatives\nadd(-1, -1) should be -2"));
+    }
+
+    #[test]
+    fn messages_stop_at_the_backtrace() {
+        assert_eq!(
+            failure_message(OUTPUT_WITH_BACKTRACE, "rusttutor_tests::adds_small_numbers").as_deref(),
+            Some("assertion `left == right` failed\n  left: 0\n right: 4")
+        );
+        assert_eq!(
+            failure_message(OUTPUT_WITH_BACKTRACE, "rusttutor_tests::prints_then_panics").as_deref(),
+            Some("checking negatives\nadd(-1, -1) should be -2")
+        );
+        assert_eq!(failure_message(OUTPUT_WITH_BACKTRACE, "rusttutor_tests::adds_zero"), None);
+    }
+
+    #[test]
+    fn no_tests() {
+        assert!(parse("\nrunning 0 tests\n\ntest result: ok. 0 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.00s\n").is_empty());
+    }
+
+    #[test]
+    fn tests_are_appended_in_their_module() {
+        let source = with_tests("fn main() {}\n\n", "#[test]\nfn t() {}\n");
+        assert_eq!(source, "fn main() {}\n\n#[cfg(test)]\nmod rusttutor_tests {\n#[allow(unused_imports)]\nuse super::*;\n\n#[test]\nfn t() {}\n}\n");
+    }
+}
@@ -1,22 +1,102 @@
+mod builtin_editor;
+mod cases;
+mod cli;
+mod compile_fail;
+mod config;
+mod content;
+mod diagnostics;
+mod diff;
+mod editor;
+mod exit;
+mod libtest;
+mod matcher;
+mod paths;
+mod progress;
+mod project;
+mod prompt;
+mod rbe;
+mod report;
+mod runner;
+mod search;
+mod selftest;
+mod session;
+mod slug;
+mod style;
+mod tui;
+mod watch;
+mod workspace;
+
+use cases::Case;
+use clap::Parser;
+use cli::Cli;
+use compile_fail::{CompileFail, Verdict};
+use diagnostics::Compilation;
+use config::Config;
+use editor::Editor;
+use exit::{ExpectedExit, Panic};
+use libtest::TestStatus;
+use matcher::Matcher;
+use progress::Progress;
+use project::CargoProject;
+use report::{CheckReport, CompileReport, RunReport, TestReport};
+use runner::{Input, Limits, Outcome, RunResult};
+use style::{paint, DIM, RESET};
+use workspace::Workspace;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::io::{self, Write};
-use std::process::Command;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitCode};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 struct Exercise {
     title: String,
+    // Stable name within its chapter, e.g. "structures"; see slug.rs
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    slug: String,
     description: String,
     initial_code: String,
     expected_output: Option<String>,
     hint: Option<String>,
     solution: Option<String>,
+    #[serde(default)]
     instructions: Vec<String>,
+    #[serde(default, skip_serializing_if = "Matcher::is_exact")]
+    matcher: Matcher,
+    // Hidden #[test] functions; when present the exercise is graded by
+    // running them instead of by comparing output
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    tests: Option<String>,
+    // The code is meant to be rejected by the compiler with these errors
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    compile_fail: Option<CompileFail>,
+    // Built as a Cargo project, with `initial_code` as src/main.rs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    cargo: Option<CargoProject>,
+    // Other source files next to main.rs, by path relative to it, e.g.
+    // `my_mod.rs` or `my_mod/nested.rs`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    files: BTreeMap<String, String>,
+    // Runs with their own args, stdin and environment; when present they
+    // replace the single run checked against `expected_output`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    cases: Vec<Case>,
+    // How the program must finish; by default it must not fail or panic
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    exit: Option<ExpectedExit>,
+    // Text the program must print to stderr
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    expected_stderr: Option<String>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 struct Chapter {
     title: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    slug: String,
     description: String,
+    #[serde(default, skip_serializing)]
     exercises: Vec<Exercise>,
 }
 
@@ -24,25 +104,97 @@ struct Tutorial {
     chapters: Vec<Chapter>,
     current_chapter: usize,
     current_exercise: usize,
-    temp_file: String,
+    workspace: Workspace,
+    editor: Editor,
     current_code: String,
+    current_files: BTreeMap<String, String>,
+    progress: Progress,
+    limits: Limits,
+    // Run programs with RUST_BACKTRACE=1
+    backtrace: bool,
+    // Whether progress is written to disk; off for one-off CLI commands
+    persist: bool,
+    // What the last check found, for `check --format json`
+    report: CheckReport,
 }
 
 impl Tutorial {
-    fn new() -> Self {
-        let chapters = Self::create_chapters();
-        let current_code = if !chapters.is_empty() && !chapters[0].exercises.is_empty() {
-            chapters[0].exercises[0].initial_code.clone()
-        } else {
-            String::new()
-        };
-        
-        Self {
+    fn new(workspace: Workspace, editor: Editor, backtrace: bool) -> Self {
+        let mut chapters = content::load_chapters().unwrap_or_else(Self::create_chapters);
+        slug::assign(&mut chapters);
+        let mut progress = Progress::load();
+        progress.upgrade(|id| slug::find(&chapters, id).map(|(chapter, exercise)| slug::key(&chapters, chapter, exercise)));
+
+        // Resume where the learner left off, unless that exercise is gone
+        let current = progress.current.as_deref().and_then(|key| slug::find(&chapters, key));
+        let (current_chapter, current_exercise) = current.unwrap_or((0, 0));
+
+        let mut tutorial = Self {
             chapters,
-            current_chapter: 0,
-            current_exercise: 0,
-            temp_file: "rusttutor_temp.rs".to_string(),
-            current_code,
+            current_chapter,
+            current_exercise,
+            workspace,
+            editor,
+            current_code: String::new(),
+            current_files: BTreeMap::new(),
+            progress,
+            limits: Limits::default(),
+            backtrace,
+            persist: true,
+            report: CheckReport::default(),
+        };
+        tutorial.load_exercise_code();
+        tutorial
+    }
+
+    // What progress is saved under, e.g. "custom-types/structures"
+    fn exercise_key(&self) -> String {
+        slug::key(&self.chapters, self.current_chapter, self.current_exercise)
+    }
+
+    // Where the exercise is now, e.g. "3.1"
+    fn exercise_number(&self) -> String {
+        format!("{}.{}", self.current_chapter + 1, self.current_exercise + 1)
+    }
+
+    // Restore the learner's saved code for the current exercise, or its initial code
+    fn load_exercise_code(&mut self) {
+        let key = self.exercise_key();
+        let exercise = self.chapters.get(self.current_chapter).and_then(|c| c.exercises.get(self.current_exercise));
+        self.current_code = match self.progress.code.get(&key) {
+            Some(code) => code.clone(),
+            None => match exercise {
+                Some(exercise) => exercise.initial_code.clone(),
+                None => String::new(),
+            },
+        };
+        self.current_files = match self.progress.files.get(&key) {
+            Some(files) => files.clone(),
+            None => exercise.map(|exercise| exercise.files.clone()).unwrap_or_default(),
+        };
+    }
+
+    // Record the current position and code, and write the progress file
+    fn save_progress(&mut self) {
+        if !self.persist {
+            return;
+        }
+        let key = self.exercise_key();
+        let exercise = &self.chapters[self.current_chapter].exercises[self.current_exercise];
+        if self.current_code == exercise.initial_code {
+            self.progress.code.remove(&key);
+        } else {
+            self.progress.code.insert(key.clone(), self.current_code.clone());
+        }
+        if self.current_files == exercise.files {
+            self.progress.files.remove(&key);
+        } else {
+            self.progress.files.insert(key, self.current_files.clone());
+        }
+        self.progress.current = Some(self.exercise_key());
+
+        if let Err(e) = self.progress.save(&self.exercise_key()) {
+            println!("⚠️  Could not save progress to {}: {}", Progress::path().display(), e);
         }
     }
 
@@ -50,10 +202,12 @@ impl Tutorial {
         vec![
             Chapter {
                 title: "Hello World".to_string(),
+                slug: "hello-world".to_string(),
                 description: "Start with a traditional Hello World program.".to_string(),
                 exercises: vec![
                     Exercise {
                         title: "Basic Hello World".to_string(),
+                        slug: "basic".to_string(),
                         description: "Write your first Rust program that prints 'Hello, World!' to the screen.".to_string(),
                         initial_code: r#"// This is a comment, and is ignored by the compiler
 // You can test this code by clicking the "Run" button over there ->
@@ -82,9 +236,11 @@ fn main() {
                             "4. Try changing the text inside the quotes".to_string(),
                             "5. Press 'r' to run the code and see the output".to_string(),
                         ],
+                        ..Default::default()
                     },
                     Exercise {
                         title: "Comments and Documentation".to_string(),
+                        slug: "comments".to_string(),
                         description: "Learn about different types of comments in Rust.".to_string(),
                         initial_code: r#"fn main() {
     // This is a line comment
@@ -115,15 +271,18 @@ fn main() {
                             "4. /// for documentation comments".to_string(),
                             "5. Add a println! statement to make the program output 'I can comment!'".to_string(),
                         ],
+                        ..Default::default()
                     },
                 ],
             },
             Chapter {
                 title: "Primitives".to_string(),
+                slug: "primitives".to_string(),
                 description: "Learn about signed integers, unsigned integers and other primitives.".to_string(),
                 exercises: vec![
                     Exercise {
                         title: "Scalar Types".to_string(),
+                        slug: "scalar-types".to_string(),
                         description: "Explore Rust's scalar types: integers, floats, booleans, and characters.".to_string(),
                         initial_code: r#"fn main() {
     // Variables can be type annotated.
@@ -177,9 +336,6 @@ fn main() {
     // Error! The type of a variable can't be changed.
     // mutable = true;
     
-    // Variables can be overwritten with shadowing.
-    let mutable = true;
-    
     // Print all the variables above
     println!("logical: {}", logical);
     println!("a_float: {}", a_float);
@@ -188,6 +344,10 @@ fn main() {
     println!("default_integer: {}", default_integer);
     println!("inferred_type: {}", inferred_type);
     println!("mutable: {}", mutable);
+    
+    // Variables can be overwritten with shadowing.
+    let mutable = true;
+    println!("mutable: {}", mutable);
 }"#.to_string()),
                         instructions: vec![
                             "1. Rust has several primitive types".to_string(),
@@ -196,15 +356,18 @@ fn main() {
                             "4. Shadowing allows reusing variable names".to_string(),
                             "5. Add println! statements to print all the variables".to_string(),
                         ],
+                        ..Default::default()
                     },
                 ],
             },
             Chapter {
                 title: "Custom Types".to_string(),
+                slug: "custom-types".to_string(),
                 description: "Learn about structs and enums.".to_string(),
                 exercises: vec![
                     Exercise {
                         title: "Structures".to_string(),
+                        slug: "structures".to_string(),
                         description: "Define and use custom structs.".to_string(),
                         initial_code: r#"// A struct with named fields
 struct Person {
@@ -289,15 +452,18 @@ fn main() {
                             "4. #[derive(Debug)] allows a struct to be printed with {:?}".to_string(),
                             "5. Add #[derive(Debug)] above the Person struct to make it printable".to_string(),
                         ],
+                        ..Default::default()
                     },
                 ],
             },
             Chapter {
                 title: "Variable Bindings".to_string(),
+                slug: "variable-bindings".to_string(),
                 description: "Learn about mutable bindings, scope, and shadowing.".to_string(),
                 exercises: vec![
                     Exercise {
                         title: "Mutability".to_string(),
+                        slug: "mutability".to_string(),
                         description: "Understand mutable and immutable bindings.".to_string(),
                         initial_code: r#"fn main() {
     let _immutable_binding = 1;
@@ -346,15 +512,18 @@ fn main() {
                             "4. Mutable variables can be modified".to_string(),
                             "5. Create a mutable counter variable and increment it by 5".to_string(),
                         ],
+                        ..Default::default()
                     },
                 ],
             },
             Chapter {
                 title: "Functions".to_string(),
+                slug: "functions".to_string(),
                 description: "Learn about functions, methods, and closures.".to_string(),
                 exercises: vec![
                     Exercise {
                         title: "Basic Functions".to_string(),
+                        slug: "basic".to_string(),
                         description: "Define and call functions with parameters and return values.".to_string(),
                         initial_code: r#"// Unlike C/C++, there's no restriction on the order of function definitions
 fn main() {
@@ -450,112 +619,54 @@ fn add(a: i32, b: i32) -> i32 {
                             "4. The last expression in a function is returned (no semicolon)".to_string(),
                             "5. Create an 'add' function that takes two i32s and returns their sum".to_string(),
                         ],
+                        ..Default::default()
                     },
                 ],
             },
         ]
     }
 
-    fn run(&mut self) {
-        println!("\x1b[2J\x1b[H"); // Clear screen
-        println!("🦀 Welcome to RustTutor - Interactive Rust Learning!");
-        println!("=================================================");
-        println!("Based on Rust by Example (https://doc.rust-lang.org/rust-by-example/)");
-        println!("Inspired by vimtutor\n");
-        
-        println!("This is an interactive tutorial. You'll be guided through each exercise step by step.");
-        println!("Press Enter to start your first exercise, or type 'help' for commands.\n");
-        
-        loop {
-            // Interactive exercise flow
-            self.interactive_exercise_flow();
-            
-            // Command mode
-            print!("\nrusttutor> ");
-            io::stdout().flush().unwrap();
-            
-            let mut input = String::new();
-            io::stdin().read_line(&mut input).unwrap();
-            let input = input.trim();
-            
-            match input {
-                "q" | "quit" => {
-                    println!("Happy coding with Rust! 🦀");
-                    break;
-                }
-                "h" | "help" => self.print_help(),
-                "l" | "list" => self.list_chapters(),
-                "r" | "run" => self.run_current_exercise(),
-                "s" | "solution" => self.show_solution(),
-                "hint" => self.show_hint(),
-                "n" | "next" => self.next_exercise(),
-                "p" | "prev" => self.prev_exercise(),
-                "edit" => self.edit_exercise(),
-                "reset" => self.reset_exercise(),
-                "restart" => self.restart_exercise(),
-                cmd if cmd.starts_with("goto ") => {
-                    let parts: Vec<&str> = cmd.split_whitespace().collect();
-                    if parts.len() == 3 {
-                        if let (Ok(chapter), Ok(exercise)) = (parts[1].parse::<usize>(), parts[2].parse::<usize>()) {
-                            self.goto_exercise(chapter, exercise);
-                        } else {
-                            println!("Invalid chapter or exercise number!");
-                        }
-                    } else {
-                        println!("Usage: goto <chapter> <exercise>");
-                    }
-                }
-                "" => continue, // Just pressed enter, restart the interactive flow
-                _ => println!("Unknown command. Type 'h' for help."),
-            }
-        }
-    }
-
     fn print_help(&self) {
         println!("\n📖 RustTutor Commands:");
         println!("  h, help      - Show this help");
         println!("  l, list      - List all chapters and exercises");
         println!("  r, run       - Run the current exercise");
+        println!("  w, watch     - Re-run the exercise every time you save it");
+        println!("  tui          - Switch to the full-screen view");
         println!("  s, solution  - Show the solution");
         println!("  hint         - Show a hint");
         println!("  n, next      - Go to next exercise");
         println!("  p, prev      - Go to previous exercise");
-        println!("  edit         - Edit the current exercise code in nvim");
+        println!("  edit         - Edit the current exercise code in your editor");
+        println!("  edit <file>  - Edit one file of a multi-file exercise");
         println!("  reset        - Reset exercise to initial state");
-        println!("  restart      - Restart the interactive flow for current exercise");
+        println!("  restart      - Reset the exercise and show it again");
+        println!("  guide        - Turn guided mode (each exercise step by step) on or off");
         println!("  goto <c> <e> - Go to chapter c, exercise e");
+        println!("  goto <name>  - Go to an exercise by number, slug or title, e.g. goto structures");
+        println!("  search <words> - Find exercises by title, description, hint or code");
+        println!("  explain <code> - Explain a compiler error, e.g. explain E0382");
+        println!("  backtrace    - Turn panic backtraces on or off");
+        println!("  workspace    - Show the directory your code is compiled in");
         println!("  q, quit      - Exit rusttutor");
-        println!("\n💡 Tip: Just press Enter to go through the interactive exercise flow!");
+        println!("\n💡 Tip: Just press Enter to run your code, and Tab to complete a command or exercise!");
     }
 
     fn list_chapters(&self) {
         println!("\n📚 Available Chapters:");
+        let color = style::enabled();
         for (i, chapter) in self.chapters.iter().enumerate() {
             let current_marker = if i == self.current_chapter { "👉" } else { "  " };
             println!("{} Chapter {}: {}", current_marker, i + 1, chapter.title);
             for (j, exercise) in chapter.exercises.iter().enumerate() {
                 let ex_marker = if i == self.current_chapter && j == self.current_exercise { "  👉" } else { "    " };
-                println!("{}  {}.{} {}", ex_marker, i + 1, j + 1, exercise.title);
+                let passed_marker = if self.progress.passed.contains(&slug::key(&self.chapters, i, j)) { " ✅" } else { "" };
+                let slug = slug::key(&self.chapters, i, j);
+                println!("{}  {}.{} {} {}({}){}{}", ex_marker, i + 1, j + 1, exercise.title, paint(color, DIM), slug, paint(color, RESET), passed_marker);
             }
         }
     }
 
-    fn interactive_exercise_flow(&mut self) {
-        // Step 1: Display chapter and exercise title
-        self.display_chapter_and_exercise();
-        self.wait_for_enter("Press Enter to see the description and instructions...");
-        
-        // Step 2: Show description and instructions
-        self.display_description_and_instructions();
-        self.wait_for_enter("Press Enter to open the code in nvim...");
-        
-        // Step 3: Open code in nvim
-        self.edit_exercise();
-        
-        // Step 4: Ask what to do next
-        self.post_edit_options();
-    }
-    
     fn display_chapter_and_exercise(&self) {
         println!("\x1b[2J\x1b[H"); // Clear screen
         let chapter = &self.chapters[self.current_chapter];
@@ -577,6 +688,10 @@ fn add(a: i32, b: i32) -> i32 {
     
     fn display_description_and_instructions(&self) {
         println!("\x1b[2J\x1b[H"); // Clear screen
+        self.print_exercise();
+    }
+    
+    fn print_exercise(&self) {
         let chapter = &self.chapters[self.current_chapter];
         let exercise = &chapter.exercises[self.current_exercise];
         
@@ -594,18 +709,93 @@ fn add(a: i32, b: i32) -> i32 {
             }
         }
         
-        if let Some(expected) = &exercise.expected_output {
+        if !exercise.cases.is_empty() {
+            println!("\n🎯 Your program will be run {} times:", exercise.cases.len());
+            if let Some(description) = exercise.matcher.describe() {
+                println!("   ({})", description);
+            }
+            for (i, case) in exercise.cases.iter().enumerate() {
+                println!("\n  {}", case.title(i));
+                for line in case.describe_input() {
+                    println!("     {}", line);
+                }
+                if let Some(expected) = &case.expected_output {
+                    println!("     expected output:");
+                    for line in expected.lines() {
+                        println!("       {}", line);
+                    }
+                }
+                if let Some(exit) = &case.exit {
+                    println!("     should {}", exit);
+                }
+                if let Some(expected) = &case.expected_stderr {
+                    println!("     error output should contain: {}", expected.trim());
+                }
+            }
+        } else if let Some(expected) = &exercise.expected_output {
             println!("\n🎯 Expected Output:");
+            if let Some(description) = exercise.matcher.describe() {
+                println!("   ({})", description);
+            }
             println!("{}", "─".repeat(30));
             println!("{}", expected);
             println!("{}", "─".repeat(30));
         }
         
+        if let Some(exit) = &exercise.exit {
+            println!("\n🚦 Your program should {}.", exit);
+        }
+        
+        if let Some(expected) = &exercise.expected_stderr {
+            println!("\n⚠️  Its error output should contain:");
+            println!("{}", "─".repeat(30));
+            println!("{}", expected.trim());
+            println!("{}", "─".repeat(30));
+        }
+        
+        if let Some(expectation) = &exercise.compile_fail {
+            println!("\n🚫 This code should fail to compile with {}.", expectation.describe());
+        }
+        
+        if let Some(tests) = &exercise.tests {
+            println!("\n🧪 Your code will be checked by {} hidden tests.", tests.matches("#[test]").count());
+        }
+        
+        if !exercise.files.is_empty() {
+            let names: Vec<&str> = exercise.files.keys().map(String::as_str).collect();
+            println!("\n📂 Besides main.rs, this exercise has: {}", names.join(", "));
+        }
+        
+        if let Some(project) = &exercise.cargo {
+            let dependencies = project.dependencies();
+            if dependencies.is_empty() {
+                println!("\n📦 This exercise is a Cargo project.");
+            } else {
+                println!("\n📦 This exercise is a Cargo project using: {}", dependencies.join(", "));
+            }
+        }
+        
         if exercise.hint.is_some() {
             println!("\n💭 Hint available - type 'hint' after editing to see it");
         }
     }
     
+    fn print_starting_code(&self) {
+        let exercise = &self.chapters[self.current_chapter].exercises[self.current_exercise];
+        let mut files = vec![("main.rs", exercise.initial_code.as_str())];
+        files.extend(exercise.files.iter().map(|(name, code)| (name.as_str(), code.as_str())));
+        for (name, code) in &files {
+            if files.len() == 1 {
+                println!("\n📝 Starting Code:");
+            } else {
+                println!("\n📝 {}:", name);
+            }
+            println!("{}", "─".repeat(50));
+            println!("{}", code.trim_end());
+            println!("{}", "─".repeat(50));
+        }
+    }
+    
     fn wait_for_enter(&self, message: &str) {
         println!("\n{}", message);
         print!("👉 ");
@@ -625,71 +815,594 @@ fn add(a: i32, b: i32) -> i32 {
         println!("Or just press Enter to run your code!");
     }
 
-    fn run_current_exercise(&self) {
-        // Write current code to temporary file
-        if let Err(e) = fs::write(&self.temp_file, &self.current_code) {
-            println!("❌ Error writing to temporary file: {}", e);
-            return;
-        }
+    // Returns whether the exercise passed
+    fn run_current_exercise(&mut self) -> bool {
+        let exercise = &self.chapters[self.current_chapter].exercises[self.current_exercise];
+        self.report = CheckReport::new(self.exercise_number(), self.exercise_key(), exercise.title.clone());
+        let passed = if let Some(tests) = exercise.tests.clone() {
+            self.run_exercise_tests(&tests)
+        } else if let Some(expectation) = exercise.compile_fail.clone() {
+            self.check_compile_fail(&expectation)
+        } else {
+            self.compile_and_run()
+        };
+        self.report.passed = passed;
+        self.record_result(passed);
+        passed
+    }
+
+    fn compile_and_run(&mut self) -> bool {
+        // Write current code to the workspace
+        let source_file = match self.write_sources() {
+            Ok(source_file) => source_file,
+            Err(e) => {
+                println!("❌ Error writing to temporary file: {}", e);
+                self.report.error = Some(e.to_string());
+                return false;
+            }
+        };
+        let exercise = &self.chapters[self.current_chapter].exercises[self.current_exercise];
         
         println!("\n🔧 Compiling and running your code...");
+        let mut passed = false;
         
         // Compile the code
-        let compile_output = Command::new("rustc")
-            .arg(&self.temp_file)
-            .arg("-o")
-            .arg("rusttutor_temp")
-            .output();
+        let compile_output = match &exercise.cargo {
+            Some(project) => project.build(&self.workspace.project_dir()).map(|build| (build.compilation, build.executable)),
+            None => diagnostics::rustc(&source_file, &self.workspace.binary(), &[], self.workspace.path())
+                .map(|compilation| (compilation, Some(self.workspace.binary()))),
+        };
+        if let Ok((compilation, _)) = &compile_output {
+            self.report.compile = Some(CompileReport::new(compilation));
+        }
         
         match compile_output {
-            Ok(output) => {
-                if output.status.success() {
-                    println!("✅ Compilation successful!");
+            Ok((output, None)) if output.success => println!("❌ The project built, but there is no binary to run."),
+            Ok((output, Some(binary))) => {
+                if output.success && !exercise.cases.is_empty() {
+                    Self::report_compiled(&output, &self.diagnostic_files("main.rs", &self.current_code));
+                    passed = self.run_cases(&binary);
+                } else if output.success {
+                    Self::report_compiled(&output, &self.diagnostic_files("main.rs", &self.current_code));
                     
                     // Run the compiled binary
-                    let run_output = Command::new("./rusttutor_temp").output();
+                    let run_output = runner::run(&binary, &self.program_input(Input::default()), self.workspace.path(), &self.limits);
                     
                     match run_output {
+                        Ok(run_result) if !matches!(run_result.outcome, Outcome::Exited(_)) => {
+                            self.report.runs.push(RunReport::new(None, &run_result, None, false));
+                            self.report_limit(&run_result.outcome);
+                            if !run_result.stdout.is_empty() {
+                                println!("\n📤 Output before it was stopped:");
+                                println!("{}", "─".repeat(40));
+                                println!("{}", run_result.stdout.trim_end());
+                                println!("{}", "─".repeat(40));
+                            }
+                            if !run_result.stderr.is_empty() {
+                                println!("\n⚠️  Error output:");
+                                println!("{}", run_result.stderr.trim_end());
+                            }
+                        }
                         Ok(run_result) => {
                             println!("\n📤 Your Output:");
                             println!("{}", "─".repeat(40));
-                            let output_str = String::from_utf8_lossy(&run_result.stdout);
+                            let output_str = &run_result.stdout;
                             println!("{}", output_str);
                             println!("{}", "─".repeat(40));
                             
-                            // Check against expected output
+                            // Check against expected output, exit status and error output
                             let chapter = &self.chapters[self.current_chapter];
                             let exercise = &chapter.exercises[self.current_exercise];
+                            let exit = exercise.exit.clone().unwrap_or_default();
+                            let ok = self.verify_run(&run_result, &exit, exercise.expected_output.as_deref(), exercise.expected_stderr.as_deref());
+                            let matched = self.output_matches(exercise.expected_output.as_deref(), &run_result.stdout);
+                            self.report.runs.push(RunReport::new(None, &run_result, matched, ok));
                             
-                            if let Some(expected) = &exercise.expected_output {
-                                let actual = output_str.trim().to_string();
-                                if actual == *expected {
+                            if !ok {
+                                println!("💭 Try again! Use 'edit' to modify your code or 'hint' for help.");
+                            } else if exercise.expected_output.is_some() {
+                                passed = true;
+                                if exercise.matcher.is_exact() {
                                     println!("🎉 Perfect! Your output matches exactly!");
-                                    println!("💡 You can now move to the next exercise with 'n' or 'next'");
                                 } else {
-                                    println!("🤔 Expected Output:");
-                                    println!("{}", "─".repeat(40));
-                                    println!("{}", expected);
-                                    println!("{}", "─".repeat(40));
-                                    println!("💭 Try again! Use 'edit' to modify your code or 'hint' for help.");
+                                    println!("🎉 Perfect! Your output matches!");
                                 }
-                            } else {
+                                println!("💡 You can now move to the next exercise with 'n' or 'next'");
+                            } else if exit.is_success() {
+                                passed = true;
                                 println!("✨ Code executed successfully!");
+                            } else {
+                                passed = true;
+                                println!("🎉 Perfect! Your program behaved just as expected!");
+                                println!("💡 You can now move to the next exercise with 'n' or 'next'");
                             }
                         }
-                        Err(e) => println!("❌ Error running program: {}", e),
+                        Err(e) => {
+                            println!("❌ Error running program: {}", e);
+                            self.report.error = Some(e.to_string());
+                        }
                     }
                 } else {
-                    println!("❌ Compilation failed:");
-                    println!("{}", String::from_utf8_lossy(&output.stderr));
-                    println!("💭 Use 'edit' to fix the issues or 'hint' for help.");
+                    Self::report_compile_failure(&output, &self.diagnostic_files("main.rs", &self.current_code));
                 }
             }
-            Err(e) => println!("❌ Error compiling: {}", e),
+            Ok((output, None)) => Self::report_compile_failure(&output, &self.diagnostic_files("main.rs", &self.current_code)),
+            Err(e) => {
+                println!("❌ Error compiling: {}", e);
+                self.report.error = Some(e.to_string());
+            }
         }
         
         // Cleanup
-        let _ = fs::remove_file("rusttutor_temp");
+        let _ = fs::remove_file(self.workspace.binary());
+        passed
+    }
+
+    // Whether the output matched what was expected, if anything was
+    fn output_matches(&self, expected: Option<&str>, output: &str) -> Option<bool> {
+        let matcher = &self.chapters[self.current_chapter].exercises[self.current_exercise].matcher;
+        expected.map(|expected| matcher.matches(expected, output).unwrap_or(false))
+    }
+
+    // Checks a finished run against the expected exit status, error output
+    // and output, reporting every way in which it falls short
+    fn verify_run(&self, run_result: &RunResult, exit: &ExpectedExit, expected_output: Option<&str>, expected_stderr: Option<&str>) -> bool {
+        let Outcome::Exited(status) = run_result.outcome else { return false };
+        let exercise = &self.chapters[self.current_chapter].exercises[self.current_exercise];
+        let panic = exit::parse_panic(status, &run_result.stderr);
+        let mut ok = true;
+
+        // Error output is shown, minus the panic report, unless the exercise
+        // checks it
+        let stderr = run_result.stderr.trim_end();
+        let shown = if panic.is_some() { exit::before_panic(stderr).trim_end() } else { stderr };
+        if expected_stderr.is_none() && !shown.is_empty() {
+            println!("⚠️  Error output:");
+            println!("{}", shown);
+        }
+
+        if !exit.matches(status, panic.as_ref()) {
+            ok = false;
+            match &panic {
+                Some(panic) => self.report_panic(panic),
+                None => println!("❌ Your program should {}, but it {}.", exit, exit::describe(status)),
+            }
+        } else if let Some(panic) = &panic {
+            println!("💥 Your program panicked, as expected: {}", panic.message);
+        }
+
+        if let Some(expected) = expected_stderr.filter(|expected| !stderr.contains(expected.trim())) {
+            ok = false;
+            println!("❌ Your program's error output should contain:");
+            println!("{}", "─".repeat(40));
+            println!("{}", expected.trim());
+            println!("{}", "─".repeat(40));
+            if stderr.is_empty() {
+                println!("   but it didn't print anything to stderr.");
+            } else {
+                println!("   but it was:");
+                println!("{}", stderr);
+                println!("{}", "─".repeat(40));
+            }
+        }
+
+        if let Some(expected) = expected_output {
+            match exercise.matcher.matches(expected, &run_result.stdout) {
+                Ok(true) => {}
+                Ok(false) => {
+                    ok = false;
+                    Self::report_mismatch(&exercise.matcher, expected, &run_result.stdout);
+                }
+                Err(e) => {
+                    ok = false;
+                    println!("❌ This exercise's expected output is not a valid pattern: {}", e);
+                }
+            }
+        }
+        ok
+    }
+
+    fn report_panic(&self, panic: &Panic) {
+        match &panic.location {
+            Some(location) => println!("💥 Your program panicked at {}:", location),
+            None => println!("💥 Your program panicked:"),
+        }
+        for line in panic.message.lines() {
+            println!("   {}", line);
+        }
+        match &panic.backtrace {
+            Some(backtrace) => {
+                println!("\n📚 Backtrace:");
+                println!("{}", backtrace);
+            }
+            None if !self.backtrace => println!("💡 Type 'backtrace' to see where it came from next time."),
+            None => {}
+        }
+    }
+
+    // Adds the tutor's own settings to what a program is started with
+    fn program_input(&self, mut input: Input) -> Input {
+        if self.backtrace {
+            input.env.entry("RUST_BACKTRACE".to_string()).or_insert_with(|| "1".to_string());
+        }
+        input
+    }
+
+    fn toggle_backtrace(&mut self) {
+        self.backtrace = !self.backtrace;
+        if self.backtrace {
+            println!("📚 Backtraces are on: a panic now shows how your program got there.");
+        } else {
+            println!("📚 Backtraces are off.");
+        }
+    }
+
+    // Runs the program once per test case and reports how each one went
+    fn run_cases(&mut self, binary: &Path) -> bool {
+        let exercise = &self.chapters[self.current_chapter].exercises[self.current_exercise];
+        let cases = exercise.cases.clone();
+        let total = cases.len();
+        let mut passed = 0;
+        for (i, case) in cases.iter().enumerate() {
+            println!("\n🧪 {}", case.title(i));
+            for line in case.describe_input() {
+                println!("   {}", line);
+            }
+
+            let run_result = match runner::run(binary, &self.program_input(case.input()), self.workspace.path(), &self.limits) {
+                Ok(run_result) => run_result,
+                Err(e) => {
+                    println!("❌ Error running program: {}", e);
+                    self.report.error = Some(e.to_string());
+                    continue;
+                }
+            };
+            if !matches!(run_result.outcome, Outcome::Exited(_)) {
+                self.report.runs.push(RunReport::new(case.name.clone(), &run_result, None, false));
+                self.report_limit(&run_result.outcome);
+                continue;
+            }
+
+            let exit = case.exit.clone().unwrap_or_default();
+            let expected_output = case.expected_output.as_deref();
+            let ok = self.verify_run(&run_result, &exit, expected_output, case.expected_stderr.as_deref());
+            let matched = self.output_matches(expected_output, &run_result.stdout);
+            self.report.runs.push(RunReport::new(case.name.clone(), &run_result, matched, ok));
+            if !ok {
+                let matcher = &self.chapters[self.current_chapter].exercises[self.current_exercise].matcher;
+                if expected_output.is_some_and(|expected| matcher.diffable(expected).is_none()) {
+                    println!("📤 Your Output:");
+                    println!("{}", "─".repeat(40));
+                    println!("{}", run_result.stdout.trim_end());
+                    println!("{}", "─".repeat(40));
+                }
+            } else {
+                passed += 1;
+                println!("✅ Passed");
+            }
+        }
+
+        println!("\n{}", "─".repeat(40));
+        if passed == total {
+            println!("🎉 Perfect! All {} cases passed!", total);
+            println!("💡 You can now move to the next exercise with 'n' or 'next'");
+            true
+        } else {
+            println!("📊 {} of {} cases passed.", passed, total);
+            println!("💭 Try again! Use 'edit' to modify your code or 'hint' for help.");
+            false
+        }
+    }
+
+    // Writes main.rs and the exercise's other files where the exercise
+    // expects them, and returns the path of main.rs
+    fn write_sources(&self) -> io::Result<PathBuf> {
+        let exercise = &self.chapters[self.current_chapter].exercises[self.current_exercise];
+        let source_file = match &exercise.cargo {
+            Some(project) => {
+                let dir = self.workspace.project_dir();
+                project.write(&dir)?;
+                dir.join(project::SOURCE_DIR).join("main.rs")
+            }
+            None => self.workspace.source_file(),
+        };
+        let dir = source_file.parent().unwrap_or(self.workspace.path());
+        // Files of the exercise before this one would otherwise still count as modules
+        let keep: Vec<PathBuf> = std::iter::once(PathBuf::from("main.rs")).chain(self.current_files.keys().map(PathBuf::from)).collect();
+        let project_dir = self.workspace.project_dir();
+        workspace::remove_stale_sources(dir, &keep, Some(&project_dir))?;
+        fs::write(&source_file, &self.current_code)?;
+
+        for (name, code) in &self.current_files {
+            let path = dir.join(name);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(path, code)?;
+        }
+        Ok(source_file)
+    }
+
+    // Reads main.rs and the other files back after the learner edited them
+    fn read_sources(&self, source_file: &Path) -> io::Result<(String, BTreeMap<String, String>)> {
+        let dir = source_file.parent().unwrap_or(self.workspace.path());
+        let code = fs::read_to_string(source_file)?;
+        let files = self
+            .current_files
+            .keys()
+            .map(|name| Ok((name.clone(), fs::read_to_string(dir.join(name))?)))
+            .collect::<io::Result<_>>()?;
+        Ok((code, files))
+    }
+
+    // The learner's files under the names rustc uses in diagnostics, with
+    // `main_name` standing in for main.rs
+    fn diagnostic_files<'a>(&'a self, main_name: &str, main_code: &'a str) -> Vec<(String, &'a str)> {
+        let exercise = &self.chapters[self.current_chapter].exercises[self.current_exercise];
+        let prefix = if exercise.cargo.is_some() { format!("{}/", project::SOURCE_DIR) } else { String::new() };
+        std::iter::once((main_name, main_code))
+            .chain(self.current_files.iter().map(|(name, code)| (name.as_str(), code.as_str())))
+            .map(|(name, code)| (format!("{}{}", prefix, name), code))
+            .collect()
+    }
+
+    // The text of one of the learner's files
+    fn source_text(&self, name: &str) -> &str {
+        match name {
+            "main.rs" => &self.current_code,
+            _ => self.current_files.get(name).map(String::as_str).unwrap_or_default(),
+        }
+    }
+
+    fn record_result(&mut self, passed: bool) {
+        if passed {
+            let key = self.exercise_key();
+            self.progress.passed.insert(key);
+        }
+        self.save_progress();
+    }
+
+    // Compiles the code expecting rustc to reject it with specific errors
+    fn check_compile_fail(&mut self, expectation: &CompileFail) -> bool {
+        let source_file = match self.write_sources() {
+            Ok(source_file) => source_file,
+            Err(e) => {
+                println!("❌ Error writing to temporary file: {}", e);
+                self.report.error = Some(e.to_string());
+                return false;
+            }
+        };
+
+        println!("\n🔧 Compiling your code (this exercise expects it to fail)...");
+        let compile_output = diagnostics::rustc(&source_file, &self.workspace.binary(), &[], self.workspace.path());
+        let _ = fs::remove_file(self.workspace.binary());
+
+        let output = match compile_output {
+            Ok(output) => output,
+            Err(e) => {
+                println!("❌ Error compiling: {}", e);
+                self.report.error = Some(e.to_string());
+                return false;
+            }
+        };
+        self.report.compile = Some(CompileReport::new(&output));
+        let rendered = diagnostics::render(&output, &self.diagnostic_files("main.rs", &self.current_code));
+
+        match expectation.check(output.success, &output.diagnostics) {
+            Ok(Verdict::Passed) => {
+                println!("🎉 Perfect! rustc rejected the code with {}, just as expected.", expectation.describe());
+                println!("\n📋 What the compiler said:");
+                println!("{}", rendered.trim_end());
+                println!("\n💡 You can now move to the next exercise with 'n' or 'next'");
+                true
+            }
+            Ok(Verdict::Compiled) => {
+                println!("🤔 Your code compiled, but this exercise expects it to fail with {}.", expectation.describe());
+                println!("💭 Use 'edit' to change your code or 'hint' for help.");
+                false
+            }
+            Ok(Verdict::WrongErrors { missing, unexpected }) => {
+                println!("🤔 rustc rejected the code, but not with the errors this exercise expects.");
+                for error in &missing {
+                    println!("   ➖ expected but not produced: {}", error);
+                }
+                for error in &unexpected {
+                    println!("   ➕ not expected: {}", error);
+                }
+                println!("\n📋 What the compiler said:");
+                println!("{}", rendered.trim_end());
+                println!("💭 Use 'edit' to change your code or 'hint' for help.");
+                false
+            }
+            Err(e) => {
+                println!("❌ This exercise's expected error message is not a valid pattern: {}", e);
+                false
+            }
+        }
+    }
+
+    // Builds the learner's code together with the exercise's hidden tests
+    // and reports each test's result
+    fn run_exercise_tests(&mut self, tests: &str) -> bool {
+        let test_file = self.workspace.test_source_file();
+        let test_binary = self.workspace.test_binary();
+        let test_source = libtest::with_tests(&self.current_code, tests);
+        // The other files must be there for the `mod` declarations in main.rs
+        if let Err(e) = self.write_sources().and_then(|_| fs::write(&test_file, &test_source)) {
+            println!("❌ Error writing to temporary file: {}", e);
+            self.report.error = Some(e.to_string());
+            return false;
+        }
+
+        println!("\n🔧 Compiling your code with the exercise's tests...");
+        let compile_output = diagnostics::rustc(&test_file, &test_binary, &["--test"], self.workspace.path());
+        let file_name = test_file.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+        if let Ok(compilation) = &compile_output {
+            let mut report = CompileReport::new(compilation);
+            // The learner's code is at the top of the test file; the hidden tests follow it
+            report.rename_file(&file_name, "main.rs", self.current_code.trim_end().lines().count());
+            self.report.compile = Some(report);
+        }
+
+        let mut passed = false;
+        match compile_output {
+            Ok(output) if output.success => {
+                Self::report_compiled(&output, &self.diagnostic_files(&file_name, &test_source));
+                match runner::run(&test_binary, &Input::default(), self.workspace.path(), &self.limits) {
+                    Ok(run_result) if !matches!(run_result.outcome, Outcome::Exited(_)) => {
+                        self.report_limit(&run_result.outcome);
+                        self.report.runs.push(RunReport::new(None, &run_result, None, false));
+                    }
+                    Ok(run_result) => {
+                        let results = libtest::parse(&run_result.stdout);
+                        self.report.tests = results.iter().map(TestReport::from).collect();
+                        if results.is_empty() {
+                            println!("❌ No tests were run:");
+                            println!("{}", run_result.stdout.trim_end());
+                            println!("{}", run_result.stderr.trim_end());
+                        } else {
+                            println!("\n🧪 Test Results:");
+                            println!("{}", "─".repeat(40));
+                            for result in &results {
+                                let marker = match result.status {
+                                    TestStatus::Passed => "✅",
+                                    TestStatus::Failed => "❌",
+                                    TestStatus::Ignored => "⏭️ ",
+                                };
+                                println!("{} {}", marker, result.name);
+                                if let Some(message) = &result.message {
+                                    for line in message.lines() {
+                                        println!("      {}", line);
+                                    }
+                                }
+                            }
+                            println!("{}", "─".repeat(40));
+
+                            let passed_count = results.iter().filter(|r| r.status == TestStatus::Passed).count();
+                            let failed_count = results.iter().filter(|r| r.status == TestStatus::Failed).count();
+                            println!("📊 {} of {} tests passed", passed_count, results.len());
+                            passed = failed_count == 0 && passed_count > 0;
+                            if passed {
+                                println!("🎉 Perfect! All tests pass!");
+                                println!("💡 You can now move to the next exercise with 'n' or 'next'");
+                            } else {
+                                println!("💭 Try again! Use 'edit' to modify your code or 'hint' for help.");
+                            }
+                        }
+                        self.report.runs.push(RunReport::new(None, &run_result, None, passed));
+                    }
+                    Err(e) => {
+                        println!("❌ Error running tests: {}", e);
+                        self.report.error = Some(e.to_string());
+                    }
+                }
+            }
+            Ok(output) => {
+                Self::report_compile_failure(&output, &self.diagnostic_files(&file_name, &test_source));
+            }
+            Err(e) => {
+                println!("❌ Error compiling: {}", e);
+                self.report.error = Some(e.to_string());
+            }
+        }
+
+        let _ = fs::remove_file(&test_binary);
+        passed
+    }
+
+    // Warnings don't stop the program from running, but they're shown, as
+    // they often point at the mistake
+    fn report_compiled(compilation: &Compilation, files: &[(String, &str)]) {
+        if compilation.warnings().next().is_some() {
+            println!("✅ Compilation successful! ({})\n", diagnostics::summary(compilation));
+            println!("{}", diagnostics::render(compilation, files).trim_end());
+        } else {
+            println!("✅ Compilation successful!");
+        }
+    }
+
+    fn report_compile_failure(compilation: &Compilation, files: &[(String, &str)]) {
+        if compilation.errors().next().is_none() {
+            // No diagnostics to work with, e.g. rustc itself crashed
+            println!("❌ Compilation failed:");
+            println!("{}", compilation.stderr);
+            println!("💭 Use 'edit' to fix the issues or 'hint' for help.");
+            return;
+        }
+
+        println!("❌ Compilation failed ({}):\n", diagnostics::summary(compilation));
+        print!("{}", diagnostics::render(compilation, files));
+        if let Some(code) = compilation.errors().find_map(|error| error.code()) {
+            println!("📖 Type 'explain {}' to learn more about this error.", code);
+        }
+        println!("💭 Use 'edit' to fix the issues or 'hint' for help.");
+    }
+
+    fn explain_error(&self, code: &str) {
+        // Accept "e382", "382" and "E0382" alike
+        let digits = code.trim().trim_start_matches(['E', 'e']);
+        let code = match digits.parse::<u32>() {
+            Ok(number) if digits.chars().all(|c| c.is_ascii_digit()) => format!("E{:04}", number),
+            _ => {
+                println!("❌ '{}' is not an error code. Try something like: explain E0382", code.trim());
+                return;
+            }
+        };
+
+        match Command::new("rustc").arg("--explain").arg(&code).output() {
+            Ok(output) if output.status.success() => {
+                println!("\n📖 {}", code);
+                println!("{}", "─".repeat(50));
+                println!("{}", String::from_utf8_lossy(&output.stdout).trim_end());
+                println!("{}", "─".repeat(50));
+            }
+            Ok(_) => println!("❌ rustc has no explanation for {}.", code),
+            Err(e) => println!("❌ Error running rustc --explain: {}", e),
+        }
+    }
+
+    fn report_mismatch(matcher: &Matcher, expected: &str, actual: &str) {
+        match (matcher.diffable(expected), matcher.diffable(actual)) {
+            (Some(expected), Some(actual)) => {
+                println!("🤔 Not quite! Differences from the expected output (- expected, + yours):");
+                if let Some(description) = matcher.describe() {
+                    println!("   ({})", description);
+                }
+                println!("{}", "─".repeat(40));
+                print!("{}", diff::render(&expected, &actual));
+                println!("{}", "─".repeat(40));
+                println!("📋 {}", diff::summary(&expected, &actual));
+            }
+            _ => {
+                println!("🤔 Not quite! Your output doesn't match the expected output.");
+                if let Some(description) = matcher.describe() {
+                    println!("   ({})", description);
+                }
+                println!("{}", "─".repeat(40));
+                println!("{}", expected);
+                println!("{}", "─".repeat(40));
+            }
+        }
+    }
+
+    fn report_limit(&self, outcome: &Outcome) {
+        match outcome {
+            Outcome::TimedOut => {
+                println!("⏱️  Your program was stopped after {} seconds.", self.limits.wall_time.as_secs());
+                println!("💭 Is there an infinite loop, or is it waiting for something?");
+            }
+            Outcome::CpuLimitExceeded => {
+                println!("⏱️  Your program used more than {} seconds of CPU time and was stopped.", self.limits.cpu_time.as_secs());
+            }
+            Outcome::MemoryLimitExceeded => {
+                println!("💥 Your program ran out of memory (limit: {} MiB).", self.limits.memory_bytes / (1024 * 1024));
+            }
+            Outcome::OutputLimitExceeded => {
+                println!("📜 Your program printed more than {} KiB of output and was stopped.", self.limits.output_bytes / 1024);
+            }
+            Outcome::Interrupted => println!("🛑 Your program was stopped."),
+            Outcome::Exited(_) => {}
+        }
     }
 
     fn show_solution(&self) {
@@ -718,6 +1431,7 @@ fn add(a: i32, b: i32) -> i32 {
     }
 
     fn next_exercise(&mut self) {
+        self.save_progress();
         if self.current_exercise < self.chapters[self.current_chapter].exercises.len() - 1 {
             self.current_exercise += 1;
         } else if self.current_chapter < self.chapters.len() - 1 {
@@ -728,15 +1442,14 @@ fn add(a: i32, b: i32) -> i32 {
             return;
         }
         
-        // Reset code to the new exercise's initial code
-        let chapter = &self.chapters[self.current_chapter];
-        let exercise = &chapter.exercises[self.current_exercise];
-        self.current_code = exercise.initial_code.clone();
+        self.load_exercise_code();
+        self.save_progress();
         
         println!("📖 Moved to next exercise!");
     }
 
     fn prev_exercise(&mut self) {
+        self.save_progress();
         if self.current_exercise > 0 {
             self.current_exercise -= 1;
         } else if self.current_chapter > 0 {
@@ -747,14 +1460,23 @@ fn add(a: i32, b: i32) -> i32 {
             return;
         }
         
-        // Reset code to the new exercise's initial code
-        let chapter = &self.chapters[self.current_chapter];
-        let exercise = &chapter.exercises[self.current_exercise];
-        self.current_code = exercise.initial_code.clone();
+        self.load_exercise_code();
+        self.save_progress();
         
         println!("📖 Moved to previous exercise!");
     }
 
+    // (number, slug, title) of every exercise, for completion at the prompt
+    fn exercise_names(&self) -> Vec<(String, String, String)> {
+        let mut names = Vec::new();
+        for (i, chapter) in self.chapters.iter().enumerate() {
+            for (j, exercise) in chapter.exercises.iter().enumerate() {
+                names.push((format!("{}.{}", i + 1, j + 1), slug::key(&self.chapters, i, j), exercise.title.clone()));
+            }
+        }
+        names
+    }
+
     fn goto_exercise(&mut self, chapter: usize, exercise: usize) {
         if chapter == 0 || chapter > self.chapters.len() {
             println!("❌ Invalid chapter number. Use 1-{}", self.chapters.len());
@@ -767,85 +1489,284 @@ fn add(a: i32, b: i32) -> i32 {
             return;
         }
         
+        self.save_progress();
         self.current_chapter = chapter_idx;
         self.current_exercise = exercise - 1;
+        self.load_exercise_code();
+        self.save_progress();
         
-        // Reset code to the new exercise's initial code
-        let chapter = &self.chapters[self.current_chapter];
-        let exercise = &chapter.exercises[self.current_exercise];
-        self.current_code = exercise.initial_code.clone();
-        
-        println!("📍 Jumped to Chapter {:?}, Exercise {:?}", chapter, exercise);
+        let exercise = &self.chapters[self.current_chapter].exercises[self.current_exercise];
+        println!("📍 Jumped to {} {} ({})", self.exercise_number(), exercise.title, self.exercise_key());
     }
 
-    fn edit_exercise(&mut self) {
-        // Write current code to temporary file
-        if let Err(e) = fs::write(&self.temp_file, &self.current_code) {
-            println!("❌ Error writing to temporary file: {}", e);
+    // `file` picks one file of a multi-file exercise; otherwise the learner is asked
+    fn edit_exercise(&mut self, file: Option<&str>) {
+        let editor = self.editor.clone();
+        let names = match file {
+            Some(name) if name == "main.rs" || self.current_files.contains_key(name) => vec![name.to_string()],
+            Some(name) => {
+                println!("❌ This exercise has no file '{}'.", name);
+                return;
+            }
+            None => match self.choose_files() {
+                Some(names) => names,
+                None => return,
+            },
+        };
+        if editor.is_builtin() {
+            self.edit_in_place(&names);
             return;
         }
+
+        // Write current code to the workspace
+        let source_file = match self.write_sources() {
+            Ok(source_file) => source_file,
+            Err(e) => {
+                println!("❌ Error writing to temporary file: {}", e);
+                return;
+            }
+        };
+        let dir = source_file.parent().unwrap_or(self.workspace.path());
+        let paths: Vec<PathBuf> = names.iter().map(|name| dir.join(name)).collect();
         
-        println!("🔧 Opening nvim... Save and quit (:wq) when you're done editing.");
+        if editor.is_vi_like() {
+            println!("🔧 Opening {}... Save and quit (:wq) when you're done editing.", editor.name());
+        } else {
+            println!("🔧 Opening {}... Save and close the file when you're done editing.", editor.name());
+        }
         println!("💡 The file will be automatically loaded with your code.");
         
-        // Open nvim with the temporary file
-        let nvim_result = Command::new("nvim")
-            .arg(&self.temp_file)
-            .status();
+        // Open the editor at the first TODO, if there is one
+        let editor_result = match paths.as_slice() {
+            [path] => editor.command(path, self.todo_line(&names[0])).status(),
+            _ => editor.command_all(&paths).status(),
+        };
         
-        match nvim_result {
-            Ok(status) => {
-                if status.success() {
-                    // Read the modified code back
-                    match fs::read_to_string(&self.temp_file) {
-                        Ok(modified_code) => {
-                            self.current_code = modified_code;
-                            println!("✅ Code updated successfully!");
-                            
-                            // Show a preview of the changes
-                            println!("\n📝 Your Current Code:");
-                            println!("{}", "─".repeat(50));
-                            println!("{}", self.current_code);
-                            println!("{}", "─".repeat(50));
-                        }
-                        Err(e) => println!("❌ Error reading modified file: {}", e),
+        match editor_result {
+            Ok(status) if !status.success() => {
+                println!("❌ {} exited with {}; your code was not changed.", editor.name(), status);
+            }
+            Ok(_) => {
+                // Read the modified code back
+                match self.read_sources(&source_file) {
+                    Ok((modified_code, modified_files)) if modified_code == self.current_code && modified_files == self.current_files => {
+                        println!("ℹ️  No changes were made to the code.");
                     }
-                } else {
-                    println!("❌ nvim was closed without saving properly.");
+                    Ok((modified_code, modified_files)) => self.apply_edits(modified_code, modified_files),
+                    Err(e) => println!("❌ Error reading modified file: {}", e),
                 }
             }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                println!("⚠️  {} is not installed; using the built-in editor instead.", editor.name());
+                self.edit_in_place(&names);
+            }
             Err(e) => {
-                println!("❌ Error opening nvim: {}", e);
-                println!("💡 Make sure nvim is installed and in your PATH.");
-                println!("    You can install it with: brew install neovim (macOS) or your package manager");
+                println!("❌ Error opening {}: {}", editor.name(), e);
+                println!("💡 Make sure it is installed and in your PATH, or choose another editor");
+                println!("    with $EDITOR, --editor <command> or `editor` in {}.", Config::path().display());
+            }
+        }
+    }
+
+    // Edits the named files with the built-in editor
+    fn edit_in_place(&mut self, names: &[String]) {
+        let files: Vec<(String, String)> = names.iter().map(|name| (name.clone(), self.source_text(name).to_string())).collect();
+        match builtin_editor::edit(&files, self.todo_line(&names[0])) {
+            Ok(Some(texts)) => {
+                let mut code = self.current_code.clone();
+                let mut other_files = self.current_files.clone();
+                for (name, text) in names.iter().zip(texts) {
+                    match name.as_str() {
+                        "main.rs" => code = text,
+                        _ => {
+                            other_files.insert(name.clone(), text);
+                        }
+                    }
+                }
+                if code == self.current_code && other_files == self.current_files {
+                    println!("ℹ️  No changes were made to the code.");
+                } else {
+                    self.apply_edits(code, other_files);
+                }
             }
+            Ok(None) => println!("ℹ️  Editing cancelled; your code was not changed."),
+            Err(e) => println!("❌ The built-in editor failed: {}", e),
         }
+    }
+
+    // Takes on the learner's edited code and shows what changed
+    fn apply_edits(&mut self, modified_code: String, modified_files: BTreeMap<String, String>) {
+        let changed: Vec<String> = std::iter::once("main.rs")
+            .chain(self.current_files.keys().map(String::as_str))
+            .filter(|name| match *name {
+                "main.rs" => modified_code != self.current_code,
+                name => modified_files.get(name) != self.current_files.get(name),
+            })
+            .map(str::to_string)
+            .collect();
+        self.current_code = modified_code;
+        self.current_files = modified_files;
+        self.save_progress();
+        println!("✅ Code updated successfully!");
         
-        // Clean up temp file
-        let _ = fs::remove_file(&self.temp_file);
+        // Show a preview of the changes
+        for name in &changed {
+            if self.current_files.is_empty() {
+                println!("\n📝 Your Current Code:");
+            } else {
+                println!("\n📝 {}:", name);
+            }
+            println!("{}", "─".repeat(50));
+            println!("{}", self.source_text(name));
+            println!("{}", "─".repeat(50));
+        }
+    }
+
+    // Line of the first TODO in one of the learner's files, if there is one
+    fn todo_line(&self, name: &str) -> Option<usize> {
+        self.source_text(name).lines().position(|line| line.contains("TODO")).map(|i| i + 1)
+    }
+
+    // Asks which files of a multi-file exercise to open. None when the
+    // answer doesn't name any.
+    fn choose_files(&self) -> Option<Vec<String>> {
+        let names: Vec<String> = std::iter::once("main.rs".to_string()).chain(self.current_files.keys().cloned()).collect();
+        if names.len() == 1 {
+            return Some(names);
+        }
+
+        println!("\n📂 This exercise has {} files:", names.len());
+        for (i, name) in names.iter().enumerate() {
+            println!("  {}. {}", i + 1, name);
+        }
+        println!("  a. All of them");
+        print!("Which file would you like to edit? [1] ");
+        io::stdout().flush().unwrap();
+        let mut input = String::new();
+        io::stdin().read_line(&mut input).unwrap();
+
+        match input.trim() {
+            "" => Some(vec![names[0].clone()]),
+            "a" | "all" => Some(names),
+            answer => {
+                let chosen = match answer.parse::<usize>() {
+                    Ok(number) => number.checked_sub(1).and_then(|i| names.get(i)),
+                    Err(_) => names.iter().find(|name| *name == answer),
+                };
+                match chosen {
+                    Some(name) => Some(vec![name.clone()]),
+                    None => {
+                        println!("❌ There is no file '{}'.", answer);
+                        None
+                    }
+                }
+            }
+        }
     }
 
     fn reset_exercise(&mut self) {
         let chapter = &self.chapters[self.current_chapter];
         let exercise = &chapter.exercises[self.current_exercise];
         self.current_code = exercise.initial_code.clone();
+        self.current_files = exercise.files.clone();
+        self.save_progress();
         println!("🔄 Exercise reset to initial state.");
     }
     
     fn restart_exercise(&mut self) {
         self.reset_exercise();
-        println!("🔄 Restarting interactive flow for current exercise...");
+        println!("🔄 Starting the exercise over...");
     }
 }
 
-fn main() {
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    if let Some(cli::Command::ImportRbe { src, dest }) = &cli.command {
+        return import_rbe(src, dest.clone().unwrap_or_else(content::content_dir));
+    }
+
+    let json = match &cli.command {
+        Some(command) if command.format() == cli::Format::Json => match cli::json_output() {
+            Ok(json) => Some(json),
+            Err(e) => {
+                eprintln!("❌ Could not set up JSON output: {}", e);
+                return ExitCode::FAILURE;
+            }
+        },
+        _ => None,
+    };
+
     // Check if Rust is installed
-    if Command::new("rustc").arg("--version").output().is_err() {
+    if cli.command.as_ref().is_none_or(cli::Command::needs_rustc) && Command::new("rustc").arg("--version").output().is_err() {
         println!("❌ Rust compiler (rustc) not found!");
         println!("Please install Rust from https://rustup.rs/");
-        return;
+        return ExitCode::FAILURE;
     }
+
+    let config = Config::load();
+    let editor = Editor::resolve(cli.editor.as_deref(), config.editor.as_deref());
     
-    let mut tutorial = Tutorial::new();
-    tutorial.run();
+    let workspace = match Workspace::create() {
+        Ok(workspace) => workspace,
+        Err(e) => {
+            println!("❌ Could not create a workspace directory: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    // Ctrl-C stops a running program; otherwise it quits, cleaning up the workspace
+    let workspace_dir = workspace.path().to_path_buf();
+    let handler = ctrlc::set_handler(move || {
+        if runner::interrupt() {
+            return;
+        }
+        let _ = fs::remove_dir_all(&workspace_dir);
+        println!("\nHappy coding with Rust! 🦀");
+        std::process::exit(130);
+    });
+    if let Err(e) = handler {
+        println!("⚠️  Could not install Ctrl-C handler: {}", e);
+    }
+
+    let mut tutorial = Tutorial::new(workspace, editor, config.backtrace);
+    match cli.command {
+        Some(cli::Command::Tui) => {
+            tutorial.run_tui();
+            ExitCode::SUCCESS
+        }
+        Some(command) => {
+            // One-off commands leave the learner's saved progress alone
+            tutorial.persist = false;
+            tutorial.run_command(command, json)
+        }
+        None => {
+            tutorial.run(config.aliases, cli.guided || config.guided);
+            ExitCode::SUCCESS
+        }
+    }
+}
+
+// `import-rbe <rust-by-example/src> [dest]` converts the book into a content pack
+fn import_rbe(src: &Path, dest: PathBuf) -> ExitCode {
+    let mut chapters = match rbe::import(src) {
+        Ok(chapters) => chapters,
+        Err(e) => {
+            println!("❌ Import failed: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let exercise_count: usize = chapters.iter().map(|c| c.exercises.len()).sum();
+    // Written into the pack, so renaming its files later keeps progress
+    slug::assign(&mut chapters);
+
+    match content::write_pack(&dest, &chapters) {
+        Ok(()) => {
+            println!("✅ Imported {} chapters with {} exercises into {}", chapters.len(), exercise_count, dest.display());
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            println!("❌ Could not write content pack to {}: {}", dest.display(), e);
+            ExitCode::FAILURE
+        }
+    }
 }
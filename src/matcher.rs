@@ -0,0 +1,170 @@
+// How a program's output is compared with an exercise's `expected_output`.
+//
+// In a content pack this is the `matcher` table, e.g.
+//   matcher = { kind = "unordered" }
+//   matcher = { kind = "float", tolerance = 0.01 }
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Matcher {
+    // Whole output equal after trimming surrounding whitespace
+    #[default]
+    Exact,
+    // Line by line, ignoring whitespace at either end of each line
+    Lines,
+    // `expected_output` is a regular expression the whole output must match
+    Regex,
+    // Same lines in any order, e.g. for HashMap iteration
+    Unordered,
+    // `expected_output` must appear somewhere in the output
+    Contains,
+    // Numbers may differ by up to `tolerance`; everything else must be equal
+    Float {
+        #[serde(default = "default_tolerance")]
+        tolerance: f64,
+    },
+}
+
+fn default_tolerance() -> f64 {
+    1e-6
+}
+
+impl Matcher {
+    pub fn is_exact(&self) -> bool {
+        *self == Matcher::Exact
+    }
+
+    pub fn matches(&self, expected: &str, actual: &str) -> Result<bool, String> {
+        let expected = expected.trim();
+        let actual = actual.trim();
+        Ok(match self {
+            Matcher::Exact => actual == expected,
+            Matcher::Lines => trimmed_lines(actual).eq(trimmed_lines(expected)),
+            Matcher::Regex => {
+                let pattern = Regex::new(&format!("^(?:{})$", expected)).map_err(|e| e.to_string())?;
+                pattern.is_match(actual)
+            }
+            Matcher::Unordered => sorted_lines(actual) == sorted_lines(expected),
+            Matcher::Contains => actual.contains(expected),
+            Matcher::Float { tolerance } => {
+                let number = Regex::new(r"-?\d+(?:\.\d+)?(?:[eE][-+]?\d+)?").expect("valid number pattern");
+                let actual: Vec<&str> = trimmed_lines(actual).collect();
+                let expected: Vec<&str> = trimmed_lines(expected).collect();
+                actual.len() == expected.len()
+                    && actual.iter().zip(&expected).all(|(a, e)| floats_match(&number, a, e, *tolerance))
+            }
+        })
+    }
+
+    // Shown next to the expected output so learners know how strict the check is
+    pub fn describe(&self) -> Option<String> {
+        match self {
+            Matcher::Exact => None,
+            Matcher::Lines => Some("whitespace at the start and end of lines is ignored".to_string()),
+            Matcher::Regex => Some("the output must match this regular expression".to_string()),
+            Matcher::Unordered => Some("lines may appear in any order".to_string()),
+            Matcher::Contains => Some("the output only has to contain this text".to_string()),
+            Matcher::Float { tolerance } => Some(format!("numbers may differ by up to {}", tolerance)),
+        }
+    }
+
+    // Text to diff against on a mismatch, where a diff makes sense at all
+    pub fn diffable(&self, output: &str) -> Option<String> {
+        match self {
+            Matcher::Exact | Matcher::Lines | Matcher::Float { .. } => Some(output.trim().to_string()),
+            Matcher::Unordered => Some(sorted_lines(output.trim()).join("\n")),
+            Matcher::Regex | Matcher::Contains => None,
+        }
+    }
+}
+
+fn trimmed_lines(text: &str) -> impl Iterator<Item = &str> {
+    text.lines().map(str::trim)
+}
+
+fn sorted_lines(text: &str) -> Vec<&str> {
+    let mut lines: Vec<&str> = trimmed_lines(text).collect();
+    lines.sort_unstable();
+    lines
+}
+
+// Splits both lines into alternating text and number pieces and compares
+// them piecewise.
+fn floats_match(number: &Regex, actual: &str, expected: &str, tolerance: f64) -> bool {
+    let actual_numbers: Vec<&str> = number.find_iter(actual).map(|m| m.as_str()).collect();
+    let expected_numbers: Vec<&str> = number.find_iter(expected).map(|m| m.as_str()).collect();
+    let actual_text: Vec<&str> = number.split(actual).collect();
+    let expected_text: Vec<&str> = number.split(expected).collect();
+
+    actual_text == expected_text
+        && actual_numbers.len() == expected_numbers.len()
+        && actual_numbers.iter().zip(&expected_numbers).all(|(a, e)| match (a.parse::<f64>(), e.parse::<f64>()) {
+            (Ok(a), Ok(e)) => (a - e).abs() <= tolerance,
+            _ => a == e,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exact_and_lines() {
+        assert!(Matcher::Exact.matches("Hello World!", "Hello World!\n").unwrap());
+        assert!(!Matcher::Exact.matches("Hello World!", "Hello world!").unwrap());
+        assert!(!Matcher::Exact.matches("a\nb", "a  \nb").unwrap());
+        assert!(Matcher::Lines.matches("a\nb", "  a  \nb\n").unwrap());
+        assert!(!Matcher::Lines.matches("a\nb", "a\n\nb").unwrap());
+    }
+
+    #[test]
+    fn float_tolerance() {
+        // `println!("{} {}", 0.1 + 0.2, 1.0 / 3.0)`
+        let actual = "sum: 0.30000000000000004, third: 0.3333333333333333\n";
+        let float = Matcher::Float { tolerance: 1e-6 };
+        assert!(float.matches("sum: 0.3, third: 0.333333", actual).unwrap());
+        assert!(!float.matches("sum: 0.3, third: 0.33", actual).unwrap());
+        assert!(Matcher::Float { tolerance: 0.01 }.matches("sum: 0.3, third: 0.33", actual).unwrap());
+        // The text around the numbers still has to be equal
+        assert!(!float.matches("total: 0.3, third: 0.333333", actual).unwrap());
+        assert!(float.matches("-1.5e3", "-1500").unwrap());
+        assert!(!float.matches("1\n2", "1").unwrap());
+    }
+
+    #[test]
+    fn float_tolerance_defaults_from_toml() {
+        let matcher: Matcher = toml::from_str("kind = \"float\"").unwrap();
+        assert_eq!(matcher, Matcher::Float { tolerance: 1e-6 });
+    }
+
+    #[test]
+    fn unordered() {
+        // Iterating a HashMap of scores, in whatever order it gave
+        let actual = "Yellow: 50\nBlue: 10\n";
+        assert!(Matcher::Unordered.matches("Blue: 10\nYellow: 50", actual).unwrap());
+        assert!(!Matcher::Unordered.matches("Blue: 10\nYellow: 50\nRed: 0", actual).unwrap());
+        // Repeated lines have to be repeated as often
+        assert!(!Matcher::Unordered.matches("a\na\nb", "a\nb\nb").unwrap());
+        assert_eq!(Matcher::Unordered.diffable(actual).unwrap(), "Blue: 10\nYellow: 50");
+    }
+
+    #[test]
+    fn regex_is_anchored() {
+        let pattern = r"Elapsed: \d+ms";
+        assert!(Matcher::Regex.matches(pattern, "Elapsed: 12ms\n").unwrap());
+        assert!(!Matcher::Regex.matches(pattern, "Elapsed: 12ms and more").unwrap());
+        assert!(!Matcher::Regex.matches(pattern, "Start\nElapsed: 12ms").unwrap());
+        // An alternation doesn't escape the anchors
+        assert!(!Matcher::Regex.matches("a|b", "ab").unwrap());
+        assert!(Matcher::Regex.matches("(unclosed", "x").is_err());
+    }
+
+    #[test]
+    fn contains() {
+        assert!(Matcher::Contains.matches("I'm a Rustacean!", "Hello World!\nI'm a Rustacean!").unwrap());
+        assert!(Matcher::Contains.diffable("x").is_none());
+    }
+}
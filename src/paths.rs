@@ -0,0 +1,23 @@
+use std::env;
+use std::path::PathBuf;
+
+// Per-user data directory, following the XDG base directory spec:
+// $XDG_DATA_HOME/rusttutor, falling back to ~/.local/share/rusttutor.
+pub fn data_dir() -> PathBuf {
+    match env::var_os("XDG_DATA_HOME").filter(|dir| !dir.is_empty()) {
+        Some(dir) => PathBuf::from(dir).join("rusttutor"),
+        None => home_dir().join(".local").join("share").join("rusttutor"),
+    }
+}
+
+// $XDG_CONFIG_HOME/rusttutor, falling back to ~/.config/rusttutor.
+pub fn config_dir() -> PathBuf {
+    match env::var_os("XDG_CONFIG_HOME").filter(|dir| !dir.is_empty()) {
+        Some(dir) => PathBuf::from(dir).join("rusttutor"),
+        None => home_dir().join(".config").join("rusttutor"),
+    }
+}
+
+fn home_dir() -> PathBuf {
+    env::var_os("HOME").map(PathBuf::from).unwrap_or_else(|| PathBuf::from("."))
+}
@@ -0,0 +1,201 @@
+// Learner progress is stored between sessions in the data directory:
+// the last position, which exercises were passed, and the latest edited
+// code for every exercise the learner has touched. Exercises are keyed by
+// slug, e.g. "custom-types/structures".
+//
+// Several tutors may be open at once, so a save never writes one session's
+// snapshot over the file: under a lock it re-reads the file and changes
+// only what this session did.
+
+use crate::paths;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::{self, File};
+use std::io;
+use std::os::fd::AsRawFd;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Progress {
+    // The exercise the learner was on
+    #[serde(default)]
+    pub current: Option<String>,
+    // Where older versions kept the position, by index; only read to upgrade
+    #[serde(default, skip_serializing)]
+    chapter: Option<usize>,
+    #[serde(default, skip_serializing)]
+    exercise: Option<usize>,
+    #[serde(default)]
+    pub passed: BTreeSet<String>,
+    #[serde(default)]
+    pub code: BTreeMap<String, String>,
+    // Edited files of multi-file exercises besides main.rs, by exercise
+    #[serde(default)]
+    pub files: BTreeMap<String, BTreeMap<String, String>>,
+    // Set by `upgrade`: the file still has keys in the old format
+    #[serde(skip)]
+    upgraded: bool,
+}
+
+impl Progress {
+    pub fn path() -> PathBuf {
+        paths::data_dir().join("progress.toml")
+    }
+
+    // A missing file is a fresh start; a corrupt one is reported and
+    // ignored rather than aborting the tutor.
+    pub fn load() -> Self {
+        let path = Self::path();
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(_) => return Self::default(),
+        };
+        match toml::from_str(&text) {
+            Ok(progress) => progress,
+            Err(e) => {
+                println!("⚠️  Ignoring unreadable progress file {}: {}", path.display(), e);
+                Self::default()
+            }
+        }
+    }
+
+    // Older versions keyed everything by position, e.g. "2.1". `slug` turns
+    // such an id into the slug of the exercise now at that position; ids
+    // it doesn't know are left alone.
+    pub fn upgrade(&mut self, slug: impl Fn(&str) -> Option<String>) {
+        let legacy = self.chapter.is_some() || self.passed.iter().chain(self.code.keys()).chain(self.files.keys()).any(|key| !key.contains('/'));
+        if !legacy {
+            return;
+        }
+        self.upgraded = true;
+        let rekey = |key: String| if key.contains('/') { key } else { slug(&key).unwrap_or(key) };
+        if let (None, Some(chapter), Some(exercise)) = (&self.current, self.chapter, self.exercise) {
+            self.current = slug(&format!("{}.{}", chapter + 1, exercise + 1));
+        }
+        self.passed = std::mem::take(&mut self.passed).into_iter().map(rekey).collect();
+        self.code = std::mem::take(&mut self.code).into_iter().map(|(key, code)| (rekey(key), code)).collect();
+        self.files = std::mem::take(&mut self.files).into_iter().map(|(key, files)| (rekey(key), files)).collect();
+    }
+
+    // Writes this session's position, its passed exercises and its code for
+    // `key`, the exercise it just worked on, into the file. Everything else
+    // is kept as another session may have left it, and read back in.
+    pub fn save(&mut self, key: &str) -> Result<(), String> {
+        let path = Self::path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let _lock = lock(&path.with_extension("lock")).map_err(|e| e.to_string())?;
+
+        // An unreadable file was already reported by `load`, and is replaced
+        let mut merged: Self = fs::read_to_string(&path).ok().and_then(|text| toml::from_str(&text).ok()).unwrap_or_default();
+        if self.upgraded {
+            // The old keys on disk were all converted when loading
+            merged.passed.retain(|key| key.contains('/'));
+            merged.code.retain(|key, _| key.contains('/'));
+            merged.files.retain(|key, _| key.contains('/'));
+            for (key, code) in &self.code {
+                merged.code.entry(key.clone()).or_insert_with(|| code.clone());
+            }
+            for (key, files) in &self.files {
+                merged.files.entry(key.clone()).or_insert_with(|| files.clone());
+            }
+        }
+        merged.current = self.current.clone();
+        merged.passed.extend(self.passed.iter().cloned());
+        match self.code.get(key) {
+            Some(code) => merged.code.insert(key.to_string(), code.clone()),
+            None => merged.code.remove(key),
+        };
+        match self.files.get(key) {
+            Some(files) => merged.files.insert(key.to_string(), files.clone()),
+            None => merged.files.remove(key),
+        };
+
+        let text = toml::to_string(&merged).map_err(|e| e.to_string())?;
+        // Write to a sibling file first so a crash never leaves a truncated file.
+        let tmp = path.with_extension("toml.tmp");
+        fs::write(&tmp, text).map_err(|e| e.to_string())?;
+        fs::rename(&tmp, &path).map_err(|e| e.to_string())?;
+        *self = merged;
+        Ok(())
+    }
+}
+
+// Holds an exclusive lock on `path` until the file is dropped
+fn lock(path: &Path) -> io::Result<File> {
+    let file = File::create(path)?;
+    // SAFETY: flock only takes the descriptor, which `file` keeps open
+    if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A progress.toml written before exercises had slugs
+    const LEGACY: &str = r#"chapter = 1
+exercise = 0
+passed = ["1.1", "1.2", "9.9"]
+
+[code]
+"1.2" = """
+fn main() {
+    // I can comment!
+}
+"""
+"2.1" = "fn main() {}\n"
+
+[files."4.1"]
+"helpers.rs" = "pub fn help() {}\n"
+"#;
+
+    fn slug(id: &str) -> Option<String> {
+        let slug = match id {
+            "1.1" => "hello-world/basic",
+            "1.2" => "hello-world/comments",
+            "2.1" => "primitives/scalar-types",
+            "4.1" => "custom-types/structures",
+            _ => return None,
+        };
+        Some(slug.to_string())
+    }
+
+    #[test]
+    fn upgrade_legacy_keys() {
+        let mut progress: Progress = toml::from_str(LEGACY).unwrap();
+        progress.upgrade(slug);
+        assert!(progress.upgraded);
+        assert_eq!(progress.current.as_deref(), Some("primitives/scalar-types"));
+        // Ids that no longer name an exercise are kept as they were
+        assert_eq!(progress.passed, BTreeSet::from(["hello-world/basic".to_string(), "hello-world/comments".to_string(), "9.9".to_string()]));
+        assert_eq!(progress.code.keys().collect::<Vec<_>>(), ["hello-world/comments", "primitives/scalar-types"]);
+        assert_eq!(progress.code["hello-world/comments"], "fn main() {\n    // I can comment!\n}\n");
+        assert_eq!(progress.files["custom-types/structures"]["helpers.rs"], "pub fn help() {}\n");
+
+        // Saved without the old position fields
+        let saved = toml::to_string(&progress).unwrap();
+        assert!(!saved.contains("chapter ="));
+        assert!(saved.starts_with("current = \"primitives/scalar-types\"\n"));
+    }
+
+    #[test]
+    fn upgrade_keeps_current_and_slug_keys() {
+        let mut progress: Progress =
+            toml::from_str("current = \"custom-types/structures\"\nchapter = 0\nexercise = 0\npassed = [\"1.1\", \"primitives/scalar-types\"]\n").unwrap();
+        progress.upgrade(slug);
+        assert_eq!(progress.current.as_deref(), Some("custom-types/structures"));
+        assert_eq!(progress.passed, BTreeSet::from(["hello-world/basic".to_string(), "primitives/scalar-types".to_string()]));
+    }
+
+    #[test]
+    fn nothing_to_upgrade() {
+        let mut progress: Progress = toml::from_str("current = \"hello-world/basic\"\npassed = [\"hello-world/basic\"]\n").unwrap();
+        progress.upgrade(|_| panic!("no id should need a slug"));
+        assert!(!progress.upgraded);
+        assert_eq!(progress.current.as_deref(), Some("hello-world/basic"));
+    }
+}
@@ -0,0 +1,133 @@
+// Cargo-project exercises: the learner's code is `src/main.rs` of a small
+// Cargo project, so it can depend on crates like `rand` or `regex`. The
+// project is built with `cargo build --offline`; dependencies come either
+// from the local Cargo cache or from a directory vendored with the pack
+// (`cargo vendor`), so exercises work without a network connection.
+// Only those sources are shared; the build itself stays in the session's
+// workspace.
+//
+// The learner's files themselves are written into `src/` by the tutor,
+// just as they are for single-file exercises.
+
+use crate::diagnostics::{Compilation, Diagnostic};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Instant;
+
+// Where the learner's files go inside the project
+pub const SOURCE_DIR: &str = "src";
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CargoProject {
+    // Contents of Cargo.toml
+    pub manifest: String,
+    // Vendored crates, relative to the exercise file in a content pack
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vendor: Option<PathBuf>,
+}
+
+pub struct Build {
+    pub compilation: Compilation,
+    // The binary cargo produced, if the build got that far
+    pub executable: Option<PathBuf>,
+}
+
+// One line of `cargo build --message-format=json`
+#[derive(Deserialize)]
+struct Message {
+    reason: String,
+    message: Option<Diagnostic>,
+    executable: Option<PathBuf>,
+    success: Option<bool>,
+}
+
+impl CargoProject {
+    pub fn resolve_vendor(&mut self, base: &Path) {
+        if let Some(vendor) = &self.vendor {
+            self.vendor = Some(base.join(vendor));
+        }
+    }
+
+    // Writes the manifest and, for vendored crates, the cargo config that
+    // points at them. Files are only rewritten when they change, so cargo
+    // doesn't rebuild for nothing.
+    pub fn write(&self, dir: &Path) -> io::Result<()> {
+        fs::create_dir_all(dir.join(SOURCE_DIR))?;
+        write_if_changed(&dir.join("Cargo.toml"), &self.manifest)?;
+
+        let config = dir.join(".cargo").join("config.toml");
+        match &self.vendor {
+            Some(vendor) => {
+                let vendor = vendor.canonicalize().map_err(|e| {
+                    io::Error::new(e.kind(), format!("vendored crates at {}: {}", vendor.display(), e))
+                })?;
+                let directory = toml::Value::String(vendor.to_string_lossy().into_owned());
+                fs::create_dir_all(dir.join(".cargo"))?;
+                write_if_changed(
+                    &config,
+                    &format!(
+                        "[source.crates-io]\nreplace-with = \"vendored-sources\"\n\n[source.vendored-sources]\ndirectory = {}\n",
+                        directory
+                    ),
+                )?;
+            }
+            None => match fs::remove_file(&config) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+                _ => {}
+            },
+        }
+        Ok(())
+    }
+
+    // Names of the crates listed under [dependencies]
+    pub fn dependencies(&self) -> Vec<String> {
+        toml::from_str::<toml::Table>(&self.manifest)
+            .ok()
+            .and_then(|manifest| manifest.get("dependencies")?.as_table().map(|deps| deps.keys().cloned().collect()))
+            .unwrap_or_default()
+    }
+
+    pub fn build(&self, dir: &Path) -> io::Result<Build> {
+        let start = Instant::now();
+        let output = Command::new("cargo")
+            .args(["build", "--offline", "--quiet", "--message-format=json"])
+            // Inside the session's workspace, even if the learner set their
+            // own CARGO_TARGET_DIR: one tutor's build never sees another's
+            .env("CARGO_TARGET_DIR", dir.join("target"))
+            .current_dir(dir)
+            .output()?;
+
+        let mut diagnostics = Vec::new();
+        let mut executable = None;
+        let mut success = output.status.success();
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            let Ok(message) = serde_json::from_str::<Message>(line) else { continue };
+            match message.reason.as_str() {
+                "compiler-message" => diagnostics.extend(message.message),
+                "compiler-artifact" if message.executable.is_some() => executable = message.executable,
+                "build-finished" => success = message.success.unwrap_or(success),
+                _ => {}
+            }
+        }
+
+        Ok(Build {
+            compilation: Compilation {
+                success,
+                diagnostics,
+                stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+                duration: start.elapsed(),
+            },
+            executable,
+        })
+    }
+}
+
+fn write_if_changed(path: &Path, contents: &str) -> io::Result<()> {
+    if fs::read_to_string(path).is_ok_and(|current| current == contents) {
+        return Ok(());
+    }
+    fs::write(path, contents)
+}
@@ -0,0 +1,130 @@
+// The `rusttutor>` command prompt: line editing, history kept across
+// sessions in $XDG_DATA_HOME/rusttutor/history, and tab completion of
+// command names and of exercise numbers, slugs and titles after `goto`.
+
+use crate::paths;
+use rustyline::completion::{Completer, Pair};
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{Context, Helper};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+// Everything the prompt understands, for completion
+const COMMANDS: &[&str] = &[
+    "backtrace", "edit", "explain", "goto", "guide", "help", "hint", "list", "next", "prev", "quit", "reset", "restart", "run",
+    "search", "solution", "tui", "watch", "workspace",
+];
+
+const HISTORY_SIZE: usize = 1000;
+
+pub struct Prompt {
+    editor: rustyline::Editor<Completion, DefaultHistory>,
+    history: PathBuf,
+    aliases: BTreeMap<String, String>,
+}
+
+struct Completion {
+    commands: Vec<String>,
+    // (number, slug, title) of every exercise, e.g.
+    // ("2.1", "primitives/scalar-types", "Scalar Types")
+    exercises: Vec<(String, String, String)>,
+}
+
+impl Prompt {
+    pub fn new(exercises: Vec<(String, String, String)>, aliases: BTreeMap<String, String>) -> rustyline::Result<Self> {
+        let config = rustyline::Config::builder()
+            .max_history_size(HISTORY_SIZE)?
+            .history_ignore_dups(true)?
+            .history_ignore_space(true)
+            .completion_type(rustyline::CompletionType::List)
+            .build();
+        let mut editor = rustyline::Editor::with_config(config)?;
+
+        let mut commands: Vec<String> = COMMANDS.iter().map(|command| command.to_string()).collect();
+        commands.extend(aliases.keys().cloned());
+        commands.sort();
+        commands.dedup();
+        editor.set_helper(Some(Completion { commands, exercises }));
+
+        let history = paths::data_dir().join("history");
+        // There is no history yet on the first run
+        let _ = editor.load_history(&history);
+        Ok(Self { editor, history, aliases })
+    }
+
+    // Reads one command, with aliases expanded. None when the learner
+    // pressed Ctrl-D or Ctrl-C, or stdin is closed.
+    pub fn read(&mut self, prompt: &str) -> Option<String> {
+        let line = match self.editor.readline(prompt) {
+            Ok(line) => line,
+            Err(ReadlineError::Eof | ReadlineError::Interrupted) => return None,
+            Err(e) => {
+                println!("❌ Could not read your command: {}", e);
+                return None;
+            }
+        };
+        let line = line.trim();
+        if !line.is_empty() && self.editor.add_history_entry(line).unwrap_or(false) {
+            // History is a convenience; a read-only data directory shouldn't stop the tutor
+            let _ = self.editor.append_history(&self.history);
+        }
+        Some(self.expand(line))
+    }
+
+    // Replaces an alias in the first word with what it stands for, keeping
+    // any arguments: with `c = "goto 2"`, "c 1" becomes "goto 2 1"
+    fn expand(&self, line: &str) -> String {
+        let (word, rest) = line.split_once(' ').unwrap_or((line, ""));
+        match self.aliases.get(word) {
+            Some(command) if rest.is_empty() => command.trim().to_string(),
+            Some(command) => format!("{} {}", command.trim(), rest.trim()),
+            None => line.to_string(),
+        }
+    }
+}
+
+impl Completer for Completion {
+    type Candidate = Pair;
+
+    fn complete(&self, line: &str, pos: usize, _ctx: &Context<'_>) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let line = &line[..pos];
+        if let Some(query) = line.strip_prefix("goto ") {
+            // Everything after `goto` is one argument, so titles with spaces complete too
+            let start = line.len() - query.trim_start().len();
+            let query = query.trim_start().to_lowercase();
+            let candidates = self
+                .exercises
+                .iter()
+                .filter(|(number, slug, title)| {
+                    number.starts_with(&query) || slug.contains(&query) || title.to_lowercase().contains(&query)
+                })
+                .map(|(number, slug, title)| Pair { display: format!("{:<5} {}  ({})", number, title, slug), replacement: slug.clone() })
+                .collect();
+            return Ok((start, candidates));
+        }
+        if line.contains(' ') {
+            return Ok((pos, Vec::new()));
+        }
+        let candidates = self
+            .commands
+            .iter()
+            .filter(|command| command.starts_with(line))
+            .map(|command| Pair { display: command.clone(), replacement: format!("{} ", command) })
+            .collect();
+        Ok((0, candidates))
+    }
+}
+
+impl Hinter for Completion {
+    type Hint = String;
+}
+
+impl Highlighter for Completion {}
+
+impl Validator for Completion {}
+
+impl Helper for Completion {}
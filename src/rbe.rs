@@ -0,0 +1,469 @@
+// Importer for a local checkout of the Rust by Example mdBook sources.
+//
+// SUMMARY.md drives the structure: every top-level entry becomes a chapter
+// and the pages nested under it contribute exercises. Each ```rust,editable
+// block on a page becomes one exercise, described by the prose paragraph
+// right before it and instructed by the "Activity" list that follows it.
+
+use crate::{Chapter, Exercise};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+struct SummaryEntry {
+    title: String,
+    path: PathBuf,
+    depth: usize,
+}
+
+pub fn import(src_dir: &Path) -> Result<Vec<Chapter>, String> {
+    let summary_path = src_dir.join("SUMMARY.md");
+    let summary = fs::read_to_string(&summary_path)
+        .map_err(|e| format!("could not read {}: {}", summary_path.display(), e))?;
+
+    let mut chapters: Vec<Chapter> = Vec::new();
+    for entry in parse_summary(&summary) {
+        let page = match fs::read_to_string(src_dir.join(&entry.path)) {
+            Ok(page) => page,
+            Err(e) => {
+                println!("⚠️  Skipping {}: {}", entry.path.display(), e);
+                continue;
+            }
+        };
+        let page_dir = src_dir.join(&entry.path).parent().map(Path::to_path_buf).unwrap_or_default();
+        let exercises = parse_page(&entry.title, &page, &page_dir);
+
+        if entry.depth == 0 {
+            chapters.push(Chapter {
+                title: entry.title.clone(),
+                slug: String::new(),
+                description: first_paragraph(&page).unwrap_or_else(|| entry.title.clone()),
+                exercises,
+            });
+        } else if let Some(chapter) = chapters.last_mut() {
+            chapter.exercises.extend(exercises);
+        }
+    }
+
+    chapters.retain(|chapter| !chapter.exercises.is_empty());
+    Ok(chapters)
+}
+
+// Parses the `- [Title](path.md)` list in SUMMARY.md. Draft chapters with an
+// empty link and unnumbered prefix/suffix chapters are skipped.
+fn parse_summary(summary: &str) -> Vec<SummaryEntry> {
+    let mut entries = Vec::new();
+    for line in summary.lines() {
+        let trimmed = line.trim_start();
+        let Some(item) = trimmed.strip_prefix("- ").or_else(|| trimmed.strip_prefix("* ")) else {
+            continue;
+        };
+        let Some((title, path)) = parse_link(item.trim()) else {
+            continue;
+        };
+        if path.is_empty() {
+            continue;
+        }
+        let indent = line.len() - trimmed.len();
+        entries.push(SummaryEntry {
+            title: title.to_string(),
+            path: PathBuf::from(path),
+            depth: indent / 4 + usize::from(indent % 4 != 0),
+        });
+    }
+    entries
+}
+
+fn parse_link(item: &str) -> Option<(&str, &str)> {
+    let rest = item.strip_prefix('[')?;
+    let (title, rest) = rest.split_once("](")?;
+    let (path, _) = rest.split_once(')')?;
+    Some((title, path))
+}
+
+fn parse_page(page_title: &str, page: &str, page_dir: &Path) -> Vec<Exercise> {
+    let mut exercises: Vec<Exercise> = Vec::new();
+    let mut heading = page_title.to_string();
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut last_paragraph = String::new();
+    let mut in_activity = false;
+
+    let mut lines = page.lines();
+    while let Some(line) = lines.next() {
+        let trimmed = line.trim();
+        let list_item = trimmed.strip_prefix("* ").or_else(|| trimmed.strip_prefix("- "));
+        // Pages end with the targets of `[text][ref]` links, `[ref]: url`
+        if is_link_definition(trimmed) {
+            continue;
+        }
+
+        // Anything that ends a paragraph: a fence, a heading, a list item or a blank line
+        if trimmed.is_empty() || trimmed.starts_with("```") || trimmed.starts_with('#') || list_item.is_some() {
+            flush_paragraph(&mut paragraph, &mut last_paragraph);
+            if in_activity && !last_paragraph.is_empty() {
+                if let Some(exercise) = exercises.last_mut() {
+                    exercise.instructions.push(std::mem::take(&mut last_paragraph));
+                }
+            }
+        }
+
+        if let Some(info) = trimmed.strip_prefix("```") {
+            let attrs: Vec<&str> = info.split(',').map(str::trim).collect();
+            let mut code_lines = Vec::new();
+            for code_line in lines.by_ref() {
+                if code_line.trim_start().starts_with("```") {
+                    break;
+                }
+                code_lines.push(code_line);
+            }
+
+            let runnable = attrs.first() == Some(&"rust")
+                && attrs.contains(&"editable")
+                && !attrs.iter().any(|attr| matches!(*attr, "ignore" | "compile_fail" | "no_run"));
+            if !runnable {
+                continue;
+            }
+
+            let initial_code = match expand_code(&code_lines, page_dir) {
+                Ok(code) => code,
+                Err(e) => {
+                    println!("⚠️  Skipping an example in \"{}\": {}", heading, e);
+                    continue;
+                }
+            };
+            let description = if last_paragraph.is_empty() { heading.clone() } else { last_paragraph.clone() };
+            exercises.push(Exercise { title: heading.clone(), description, initial_code, ..Default::default() });
+            in_activity = false;
+            last_paragraph.clear();
+        } else if let Some(title) = trimmed.strip_prefix('#') {
+            let title = title.trim_start_matches('#').trim();
+            in_activity = title.eq_ignore_ascii_case("activity");
+            if !in_activity {
+                heading = title.to_string();
+            }
+            last_paragraph.clear();
+        } else if let (true, Some(item), Some(exercise)) = (in_activity, list_item, exercises.last_mut()) {
+            exercise.instructions.push(strip_links(item));
+        } else if !trimmed.is_empty() {
+            paragraph.push(trimmed);
+        }
+    }
+
+    flush_paragraph(&mut paragraph, &mut last_paragraph);
+    if in_activity && !last_paragraph.is_empty() {
+        if let Some(exercise) = exercises.last_mut() {
+            exercise.instructions.push(last_paragraph);
+        }
+    }
+
+    // Several blocks under one heading would otherwise share a title
+    let titles: Vec<String> = exercises.iter().map(|e| e.title.clone()).collect();
+    for (i, exercise) in exercises.iter_mut().enumerate() {
+        let same_title = titles.iter().filter(|title| **title == titles[i]).count();
+        if same_title > 1 {
+            let n = titles[..i].iter().filter(|title| **title == titles[i]).count() + 1;
+            exercise.title = format!("{} ({})", titles[i], n);
+        }
+    }
+    exercises
+}
+
+fn flush_paragraph(paragraph: &mut Vec<&str>, last_paragraph: &mut String) {
+    if !paragraph.is_empty() {
+        *last_paragraph = strip_links(&paragraph.join(" "));
+        paragraph.clear();
+    }
+}
+
+fn is_link_definition(line: &str) -> bool {
+    line.strip_prefix('[').and_then(|rest| rest.split_once("]:")).is_some_and(|(label, _)| !label.contains(']'))
+}
+
+fn first_paragraph(page: &str) -> Option<String> {
+    page.split("\n\n")
+        .map(|block| block.trim())
+        .find(|block| !block.is_empty() && !block.starts_with('#') && !block.starts_with("```"))
+        .map(|block| strip_links(&block.lines().map(str::trim).collect::<Vec<_>>().join(" ")))
+}
+
+// Turns `[text](url)` and `[text][ref]` into plain `text`.
+fn strip_links(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('[') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let Some(end) = after.find(']') else {
+            out.push_str(&rest[start..]);
+            return out;
+        };
+        out.push_str(&after[..end]);
+        let tail = &after[end + 1..];
+        let close = match tail.chars().next() {
+            Some('(') => tail.find(')'),
+            Some('[') => tail.find(']'),
+            _ => None,
+        };
+        rest = match close {
+            Some(close) => &tail[close + 1..],
+            None => tail,
+        };
+    }
+    out.push_str(rest);
+    out
+}
+
+// mdBook hides lines prefixed with `# ` from the reader but still compiles
+// them, and pulls shared snippets in with `{{#include path}}`. An include
+// that can't be resolved fails the whole example.
+fn expand_code(lines: &[&str], page_dir: &Path) -> Result<String, String> {
+    let mut code = String::new();
+    for line in lines {
+        let trimmed = line.trim();
+        if let Some(directive) = trimmed.strip_prefix("{{#include ").and_then(|d| d.strip_suffix("}}")) {
+            let (file, selector) = match directive.trim().split_once(':') {
+                Some((file, selector)) => (file, Some(selector)),
+                None => (directive.trim(), None),
+            };
+            let included = fs::read_to_string(page_dir.join(file)).map_err(|e| format!("could not include {}: {}", file, e))?;
+            let part = match selector {
+                Some(selector) => select(&included, selector).map_err(|e| format!("could not include {}: {}", file, e))?,
+                None => included,
+            };
+            code.push_str(&part);
+            if !code.ends_with('\n') {
+                code.push('\n');
+            }
+            continue;
+        }
+        let indent = &line[..line.len() - line.trim_start().len()];
+        if trimmed == "#" {
+            code.push_str(indent);
+        } else if let Some(hidden) = line.trim_start().strip_prefix("# ") {
+            code.push_str(indent);
+            code.push_str(hidden);
+        } else {
+            code.push_str(line);
+        }
+        code.push('\n');
+    }
+    Ok(code)
+}
+
+// The part of an included file after the colon in `{{#include file:...}}`:
+// line `N`, lines `N:M`, `:M` or `N:` (1-based, inclusive), or the lines
+// between `ANCHOR: name` and `ANCHOR_END: name`, without any anchor lines
+fn select(text: &str, selector: &str) -> Result<String, String> {
+    let lines: Vec<&str> = text.lines().collect();
+    let number = |n: &str| n.trim().parse::<usize>().map_err(|_| format!("'{}' is not a line number", n));
+    let is_range = selector.chars().all(|c| c.is_ascii_digit() || c == ':');
+    let selected: Vec<&str> = if is_range {
+        let (start, end) = match selector.split_once(':') {
+            Some((start, end)) => (
+                if start.is_empty() { 1 } else { number(start)? },
+                if end.is_empty() { lines.len() } else { number(end)? },
+            ),
+            None => (number(selector)?, number(selector)?),
+        };
+        lines.iter().skip(start.max(1) - 1).take((end + 1).saturating_sub(start.max(1))).copied().collect()
+    } else {
+        let anchor = |line: &str, marker: &str| line.split_once(marker).is_some_and(|(_, name)| name.trim() == selector);
+        let start = lines.iter().position(|line| anchor(line, "ANCHOR:")).ok_or(format!("there is no anchor '{}'", selector))?;
+        lines[start + 1..]
+            .iter()
+            .take_while(|line| !anchor(line, "ANCHOR_END:"))
+            .filter(|line| !line.contains("ANCHOR:") && !line.contains("ANCHOR_END:"))
+            .copied()
+            .collect()
+    };
+    if selected.is_empty() {
+        return Err(format!("'{}' selects no lines", selector));
+    }
+    Ok(selected.join("\n") + "\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Excerpts of the Rust by Example sources
+    const SUMMARY: &str = "# Summary
+
+[Introduction](index.md)
+
+- [Hello World](hello.md)
+    - [Comments](hello/comment.md)
+    - [Formatted print](hello/print.md)
+        - [Debug](hello/print/print_debug.md)
+
+- [Primitives](primitives.md)
+- [Unsafe Operations]()
+";
+
+    const HELLO: &str = "# Hello World
+
+This is the source code of the traditional Hello World program.
+
+```rust,editable
+// This is the main function.
+fn main() {
+    // Print text to the console.
+    println!(\"Hello World!\");
+}
+```
+
+`println!` is a [*macro*][macros] that prints text to the
+console.
+
+```bash
+$ rustc hello.rs
+```
+
+### Activity
+
+Click 'Run' above to see the expected output. Next, add a new
+line with a second `println!` macro so that the output shows:
+
+```text
+Hello World!
+I'm a Rustacean!
+```
+
+[macros]: macros.md
+";
+
+    const QUESTION_MARK: &str = "# Unpacking options with `?`
+
+You can unpack `Option`s by using `match` statements, but it's often easier to
+use the `?` operator.
+
+```rust,editable
+struct Person {
+    job: Option<Job>,
+}
+#
+# #[derive(Clone, Copy)]
+# struct Job;
+
+fn main() {}
+```
+
+```rust,editable,ignore
+fn main() { let x = undefined; }
+```
+
+## See also:
+
+* [`Option`][option]
+
+Now without the [`?`][question]:
+
+```rust,editable
+fn main() {}
+```
+";
+
+    #[test]
+    fn summary_entries_and_depths() {
+        let entries: Vec<(String, String, usize)> = parse_summary(SUMMARY)
+            .into_iter()
+            .map(|entry| (entry.title, entry.path.display().to_string(), entry.depth))
+            .collect();
+        assert_eq!(
+            entries,
+            [
+                ("Hello World".to_string(), "hello.md".to_string(), 0),
+                ("Comments".to_string(), "hello/comment.md".to_string(), 1),
+                ("Formatted print".to_string(), "hello/print.md".to_string(), 1),
+                ("Debug".to_string(), "hello/print/print_debug.md".to_string(), 2),
+                ("Primitives".to_string(), "primitives.md".to_string(), 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn page_with_activity() {
+        let exercises = parse_page("Hello World", HELLO, Path::new("."));
+        assert_eq!(exercises.len(), 1);
+        let exercise = &exercises[0];
+        assert_eq!(exercise.title, "Hello World");
+        assert_eq!(exercise.description, "This is the source code of the traditional Hello World program.");
+        assert!(exercise.initial_code.starts_with("// This is the main function.\nfn main() {\n"));
+        assert!(exercise.initial_code.ends_with("    println!(\"Hello World!\");\n}\n"));
+        assert_eq!(
+            exercise.instructions,
+            ["Click 'Run' above to see the expected output. Next, add a new line with a second `println!` macro so that the output shows:"]
+        );
+    }
+
+    #[test]
+    fn page_with_hidden_lines_and_skipped_blocks() {
+        let exercises = parse_page("Unpacking options with ?", QUESTION_MARK, Path::new("."));
+        let titles: Vec<&str> = exercises.iter().map(|e| e.title.as_str()).collect();
+        assert_eq!(titles, ["Unpacking options with `?`", "See also:"]);
+        assert_eq!(
+            exercises[0].description,
+            "You can unpack `Option`s by using `match` statements, but it's often easier to use the `?` operator."
+        );
+        assert_eq!(
+            exercises[0].initial_code,
+            "struct Person {\n    job: Option<Job>,\n}\n\n#[derive(Clone, Copy)]\nstruct Job;\n\nfn main() {}\n"
+        );
+        // The list under "See also" is not an activity
+        assert!(exercises[1].instructions.is_empty());
+        assert_eq!(exercises[1].description, "Now without the `?`:");
+    }
+
+    #[test]
+    fn missing_include() {
+        let page = "# Structures\n\n```rust,editable\n{{#include ../missing.rs:7}}\n```\n";
+        assert!(parse_page("Structures", page, Path::new("/nonexistent")).is_empty());
+    }
+
+    // Shaped like the listings RBE includes from its `src` directory
+    const LISTING: &str = "use std::fmt;
+
+// ANCHOR: point
+#[derive(Debug)]
+struct Point {
+    x: f32,
+    // ANCHOR: inner
+    y: f32,
+    // ANCHOR_END: inner
+}
+// ANCHOR_END: point
+
+fn main() {}
+";
+
+    #[test]
+    fn include_parts() {
+        assert_eq!(select(LISTING, "1"), Ok("use std::fmt;\n".to_string()));
+        assert_eq!(select(LISTING, "4:5"), Ok("#[derive(Debug)]\nstruct Point {\n".to_string()));
+        assert_eq!(select(LISTING, ":2"), Ok("use std::fmt;\n\n".to_string()));
+        assert_eq!(select(LISTING, "13:"), Ok("fn main() {}\n".to_string()));
+        assert_eq!(select(LISTING, "point"), Ok("#[derive(Debug)]\nstruct Point {\n    x: f32,\n    y: f32,\n}\n".to_string()));
+        assert_eq!(select(LISTING, "inner"), Ok("    y: f32,\n".to_string()));
+        assert!(select(LISTING, "missing").is_err());
+        assert!(select(LISTING, "40:50").is_err());
+    }
+
+    #[test]
+    fn includes() {
+        let dir = std::env::temp_dir().join(format!("rusttutor-rbe-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("point.rs"), LISTING).unwrap();
+        let page = "# Structures\n\n```rust,editable\n{{#include point.rs:point}}\n\nfn main() {}\n```\n\n```rust,editable\n{{#include point.rs}}\n```\n\n```rust,editable\n{{#include point.rs:nowhere}}\n```\n";
+        let exercises = parse_page("Structures", page, &dir);
+        let _ = fs::remove_dir_all(&dir);
+
+        let code: Vec<&str> = exercises.iter().map(|e| e.initial_code.as_str()).collect();
+        assert_eq!(code, ["#[derive(Debug)]\nstruct Point {\n    x: f32,\n    y: f32,\n}\n\nfn main() {}\n", LISTING]);
+    }
+
+    #[test]
+    fn links_are_stripped() {
+        assert!(is_link_definition("[macros]: macros.md"));
+        assert!(!is_link_definition("[a](b): c"));
+        assert_eq!(strip_links("a [*macro*][macros] and [rustc](https://x) [unclosed"), "a *macro* and rustc [unclosed");
+    }
+}
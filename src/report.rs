@@ -0,0 +1,311 @@
+// Machine-readable results for `--format json`: the exercise listing,
+// learner progress and what checking an exercise found. Every document
+// carries a schema `version`; within a version fields are only ever added,
+// never renamed, removed or given a different meaning. The schema is
+// described in the README.
+
+use crate::diagnostics::{Compilation, Diagnostic};
+use crate::exit;
+use crate::libtest::{TestResult, TestStatus};
+use crate::runner::{Outcome, RunResult};
+use crate::slug;
+use crate::Tutorial;
+use serde::Serialize;
+use std::os::unix::process::ExitStatusExt;
+use std::time::Duration;
+
+pub const VERSION: u32 = 1;
+
+#[derive(Debug, Serialize)]
+pub struct Listing {
+    pub version: u32,
+    // The learner's current exercise, e.g. "2.1"
+    pub current: String,
+    pub chapters: Vec<ChapterListing>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ChapterListing {
+    pub number: usize,
+    pub slug: String,
+    pub title: String,
+    pub exercises: Vec<ExerciseListing>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExerciseListing {
+    pub id: String,
+    // Stays the same when exercises are added or moved, e.g. "custom-types/structures"
+    pub slug: String,
+    pub title: String,
+    pub passed: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProgressReport {
+    pub version: u32,
+    pub current: String,
+    pub completed: usize,
+    pub total: usize,
+    // Ids of the passed exercises
+    pub passed: Vec<String>,
+    pub chapters: Vec<ChapterProgress>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ChapterProgress {
+    pub number: usize,
+    pub title: String,
+    pub completed: usize,
+    pub total: usize,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct CheckReport {
+    pub version: u32,
+    pub exercise: String,
+    pub slug: String,
+    pub title: String,
+    pub passed: bool,
+    // Missing when the code never got as far as the compiler
+    pub compile: Option<CompileReport>,
+    // One per run of the program: a single run, or one per test case
+    pub runs: Vec<RunReport>,
+    // Results of the exercise's hidden tests
+    pub tests: Vec<TestReport>,
+    // Set when the tutor itself failed, e.g. couldn't start the compiler
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CompileReport {
+    pub success: bool,
+    pub duration_ms: u64,
+    pub errors: usize,
+    pub warnings: usize,
+    pub diagnostics: Vec<DiagnosticReport>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DiagnosticReport {
+    // "error" or "warning"
+    pub level: String,
+    pub code: Option<String>,
+    pub message: String,
+    // Where the primary span starts, if there is one
+    pub file: Option<String>,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    // The notes and help rustc attaches
+    pub notes: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RunReport {
+    // Name of the test case, if it has one
+    pub case: Option<String>,
+    // "exited", "timed_out", "cpu_limit", "memory_limit", "output_limit" or "interrupted"
+    pub outcome: &'static str,
+    pub exit_code: Option<i32>,
+    pub signal: Option<i32>,
+    pub panic: Option<PanicReport>,
+    pub stdout: String,
+    pub stderr: String,
+    pub duration_ms: u64,
+    // Whether stdout matched the expected output; missing when nothing was expected
+    pub matched: Option<bool>,
+    pub passed: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PanicReport {
+    pub location: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TestReport {
+    pub name: String,
+    // "passed", "failed" or "ignored"
+    pub status: &'static str,
+    pub message: Option<String>,
+}
+
+impl CheckReport {
+    pub fn new(exercise: String, slug: String, title: String) -> Self {
+        Self { version: VERSION, exercise, slug, title, ..Self::default() }
+    }
+
+    // A short description of why the check failed, e.g. "case 2: wrong output"
+    pub fn problem(&self) -> String {
+        if let Some(error) = &self.error {
+            return error.clone();
+        }
+        let Some(compile) = &self.compile else { return "not compiled".to_string() };
+        if !compile.success {
+            let first = compile.diagnostics.iter().find(|d| d.level == "error");
+            return match first {
+                Some(error) => format!("doesn't compile: {}", error.message),
+                None => "doesn't compile".to_string(),
+            };
+        }
+        if let Some(test) = self.tests.iter().find(|test| test.status == "failed") {
+            return format!("test {} failed", test.name);
+        }
+        let Some((i, run)) = self.runs.iter().enumerate().find(|(_, run)| !run.passed) else {
+            return "did not pass".to_string();
+        };
+        let problem = if run.outcome != "exited" {
+            run.outcome.replace('_', " ")
+        } else if let Some(panic) = &run.panic {
+            format!("panicked: {}", panic.message)
+        } else if run.matched == Some(false) {
+            "wrong output".to_string()
+        } else {
+            match run.exit_code {
+                Some(code) => format!("exited with code {}", code),
+                None => "wrong exit or stderr".to_string(),
+            }
+        };
+        match (&run.case, self.runs.len()) {
+            (Some(name), _) => format!("case {}: {}", name, problem),
+            (None, 1) => problem,
+            (None, _) => format!("case {}: {}", i + 1, problem),
+        }
+    }
+}
+
+impl CompileReport {
+    pub fn new(compilation: &Compilation) -> Self {
+        let diagnostics = compilation.diagnostics.iter().filter(|d| d.is_error() || d.is_warning());
+        Self {
+            success: compilation.success,
+            duration_ms: millis(compilation.duration),
+            errors: compilation.errors().count(),
+            warnings: compilation.warnings().count(),
+            diagnostics: diagnostics.map(DiagnosticReport::new).collect(),
+        }
+    }
+
+    // Reports the first `lines` lines of `from` as being in `to`, for code
+    // that was compiled from a copy of the learner's file
+    pub fn rename_file(&mut self, from: &str, to: &str, lines: usize) {
+        for diagnostic in &mut self.diagnostics {
+            if diagnostic.file.as_deref() == Some(from) && diagnostic.line.is_some_and(|line| line <= lines) {
+                diagnostic.file = Some(to.to_string());
+            }
+        }
+    }
+}
+
+impl DiagnosticReport {
+    fn new(diagnostic: &Diagnostic) -> Self {
+        let span = diagnostic.spans.iter().find(|span| span.is_primary);
+        Self {
+            level: diagnostic.level.clone(),
+            code: diagnostic.code().map(str::to_string),
+            message: diagnostic.message.clone(),
+            file: span.map(|span| span.file_name.clone()),
+            line: span.map(|span| span.line_start),
+            column: span.map(|span| span.column_start),
+            notes: diagnostic.children.iter().map(|child| format!("{}: {}", child.level, child.message)).collect(),
+        }
+    }
+}
+
+impl RunReport {
+    pub fn new(case: Option<String>, run_result: &RunResult, matched: Option<bool>, passed: bool) -> Self {
+        let (outcome, status) = match run_result.outcome {
+            Outcome::Exited(status) => ("exited", Some(status)),
+            Outcome::TimedOut => ("timed_out", None),
+            Outcome::CpuLimitExceeded => ("cpu_limit", None),
+            Outcome::MemoryLimitExceeded => ("memory_limit", None),
+            Outcome::OutputLimitExceeded => ("output_limit", None),
+            Outcome::Interrupted => ("interrupted", None),
+        };
+        let panic = status.and_then(|status| exit::parse_panic(status, &run_result.stderr));
+        Self {
+            case,
+            outcome,
+            exit_code: status.and_then(|status| status.code()),
+            signal: status.and_then(|status| status.signal()),
+            panic: panic.map(|panic| PanicReport { location: panic.location, message: panic.message }),
+            stdout: run_result.stdout.clone(),
+            stderr: run_result.stderr.clone(),
+            duration_ms: millis(run_result.duration),
+            matched,
+            passed,
+        }
+    }
+}
+
+impl From<&TestResult> for TestReport {
+    fn from(result: &TestResult) -> Self {
+        let status = match result.status {
+            TestStatus::Passed => "passed",
+            TestStatus::Failed => "failed",
+            TestStatus::Ignored => "ignored",
+        };
+        Self { name: result.name.clone(), status, message: result.message.clone() }
+    }
+}
+
+fn millis(duration: Duration) -> u64 {
+    duration.as_millis().try_into().unwrap_or(u64::MAX)
+}
+
+impl Tutorial {
+    pub(crate) fn listing(&self) -> Listing {
+        let chapters = self.chapters.iter().enumerate().map(|(i, chapter)| ChapterListing {
+            number: i + 1,
+            slug: chapter.slug.clone(),
+            title: chapter.title.clone(),
+            exercises: chapter
+                .exercises
+                .iter()
+                .enumerate()
+                .map(|(j, exercise)| {
+                    let slug = slug::key(&self.chapters, i, j);
+                    ExerciseListing {
+                        id: format!("{}.{}", i + 1, j + 1),
+                        passed: self.progress.passed.contains(&slug),
+                        slug,
+                        title: exercise.title.clone(),
+                    }
+                })
+                .collect(),
+        });
+        Listing { version: VERSION, current: self.exercise_number(), chapters: chapters.collect() }
+    }
+
+    // Only exercises that still exist count; the content may have changed
+    // since they were passed
+    pub(crate) fn progress_report(&self) -> ProgressReport {
+        let listing = self.listing();
+        let passed: Vec<String> = listing
+            .chapters
+            .iter()
+            .flat_map(|chapter| &chapter.exercises)
+            .filter(|exercise| exercise.passed)
+            .map(|exercise| exercise.id.clone())
+            .collect();
+        let chapters: Vec<ChapterProgress> = listing
+            .chapters
+            .into_iter()
+            .map(|chapter| ChapterProgress {
+                number: chapter.number,
+                title: chapter.title,
+                completed: chapter.exercises.iter().filter(|exercise| exercise.passed).count(),
+                total: chapter.exercises.len(),
+            })
+            .collect();
+        ProgressReport {
+            version: VERSION,
+            current: listing.current,
+            completed: passed.len(),
+            total: chapters.iter().map(|chapter| chapter.total).sum(),
+            passed,
+            chapters,
+        }
+    }
+}
@@ -0,0 +1,378 @@
+// Runs compiled learner programs under resource limits, so an infinite
+// loop or a runaway allocation can't hang or take down the tutor.
+//
+// Every program gets a wall-clock timeout, a CPU-time limit, an address
+// space cap, a cap on captured output and a cap on how many processes it
+// can start, so a fork bomb fails instead of filling the process table.
+// With isolation enabled it also runs in fresh user, mount and network
+// namespaces: there is no network and the whole filesystem is read-only
+// except the scratch directory.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::io::{self, Read, Write};
+use std::os::unix::process::{CommandExt, ExitStatusExt};
+use std::path::Path;
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+// Process group of the program currently running, or 0. Lets the Ctrl-C
+// handler stop the program instead of the whole tutor.
+static RUNNING_GROUP: AtomicI32 = AtomicI32::new(0);
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone)]
+pub struct Limits {
+    pub wall_time: Duration,
+    pub cpu_time: Duration,
+    pub memory_bytes: u64,
+    pub output_bytes: usize,
+    pub isolate: bool,
+    // Processes (and threads) the program may add. Outside a user namespace
+    // RLIMIT_NPROC counts everything the user runs, so there the limit is
+    // this many on top of what the user is already running.
+    pub max_processes: u64,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            wall_time: Duration::from_secs(10),
+            cpu_time: Duration::from_secs(5),
+            memory_bytes: 512 * 1024 * 1024,
+            output_bytes: 64 * 1024,
+            isolate: env::var("RUSTTUTOR_ISOLATE").is_ok_and(|v| v == "1"),
+            max_processes: 64,
+        }
+    }
+}
+
+// What the program is started with
+#[derive(Debug, Default)]
+pub struct Input {
+    pub args: Vec<String>,
+    pub stdin: String,
+    pub env: BTreeMap<String, String>,
+}
+
+#[derive(Debug)]
+pub enum Outcome {
+    Exited(ExitStatus),
+    TimedOut,
+    CpuLimitExceeded,
+    MemoryLimitExceeded,
+    OutputLimitExceeded,
+    Interrupted,
+}
+
+#[derive(Debug)]
+pub struct RunResult {
+    pub outcome: Outcome,
+    pub stdout: String,
+    pub stderr: String,
+    pub duration: Duration,
+}
+
+pub fn run(program: &Path, input: &Input, work_dir: &Path, limits: &Limits) -> io::Result<RunResult> {
+    let mut command = Command::new(program);
+    command
+        .args(&input.args)
+        .current_dir(work_dir)
+        // Keep output predictable whatever the tutor's own environment says
+        .env("RUST_BACKTRACE", "0")
+        .envs(&input.env)
+        .stdin(if input.stdin.is_empty() { Stdio::null() } else { Stdio::piped() })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        // Own process group, so a timeout also kills anything the program forked
+        .process_group(0);
+
+    let mut rlimits = vec![
+        (libc::RLIMIT_CPU, limits.cpu_time.as_secs().max(1)),
+        (libc::RLIMIT_AS, limits.memory_bytes),
+        (libc::RLIMIT_CORE, 0),
+    ];
+    if limits.isolate {
+        rlimits.push((libc::RLIMIT_NPROC, limits.max_processes));
+    } else if let Some(max) = process_limit(limits.max_processes) {
+        rlimits.push((libc::RLIMIT_NPROC, max));
+    }
+    #[cfg(target_os = "linux")]
+    let sandbox = if limits.isolate { Some(sandbox::prepare(work_dir)?) } else { None };
+    #[cfg(not(target_os = "linux"))]
+    if limits.isolate {
+        return Err(io::Error::new(io::ErrorKind::Unsupported, "isolation is only supported on Linux"));
+    }
+
+    // SAFETY: the closure only makes raw syscalls on data prepared before the
+    // fork, which is async-signal-safe.
+    unsafe {
+        command.pre_exec(move || {
+            for &(resource, value) in &rlimits {
+                // Going over the soft CPU limit sends SIGXCPU; the hard limit is a
+                // second later so the program can't ignore it.
+                let hard = if resource == libc::RLIMIT_CPU { value + 1 } else { value };
+                let limit = libc::rlimit { rlim_cur: value, rlim_max: hard };
+                if libc::setrlimit(resource, &limit) != 0 {
+                    return Err(io::Error::last_os_error());
+                }
+            }
+            #[cfg(target_os = "linux")]
+            if let Some(sandbox) = &sandbox {
+                sandbox.enter()?;
+            }
+            Ok(())
+        });
+    }
+
+    let start = Instant::now();
+    let mut child = command.spawn()?;
+    INTERRUPTED.store(false, Ordering::SeqCst);
+    RUNNING_GROUP.store(child.id() as i32, Ordering::SeqCst);
+    let overflowed = Arc::new(AtomicBool::new(false));
+    let stdout = capture(child.stdout.take(), limits.output_bytes, &overflowed);
+    let stderr = capture(child.stderr.take(), limits.output_bytes, &overflowed);
+    if let Some(mut stdin) = child.stdin.take() {
+        // From a thread, so a program that doesn't read all of its input
+        // can't block us; it just gets a broken pipe
+        let text = input.stdin.clone();
+        thread::spawn(move || {
+            let _ = stdin.write_all(text.as_bytes());
+        });
+    }
+
+    let mut outcome = None;
+    while outcome.is_none() {
+        if let Some(status) = child.try_wait()? {
+            // Take down anything it left running in the background, which
+            // would otherwise keep the output pipes open
+            kill_group(&mut child)?;
+            outcome = Some(if INTERRUPTED.load(Ordering::SeqCst) { Outcome::Interrupted } else { classify(status) });
+        } else if start.elapsed() >= limits.wall_time {
+            kill_group(&mut child)?;
+            outcome = Some(Outcome::TimedOut);
+        } else if overflowed.load(Ordering::Relaxed) {
+            kill_group(&mut child)?;
+            outcome = Some(Outcome::OutputLimitExceeded);
+        } else {
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+    RUNNING_GROUP.store(0, Ordering::SeqCst);
+
+    let stdout = stdout.join().unwrap_or_default();
+    let stderr = stderr.join().unwrap_or_default();
+    let outcome = match outcome.unwrap() {
+        // Rust aborts with this message when an allocation hits RLIMIT_AS
+        Outcome::Exited(status)
+            if status.signal() == Some(libc::SIGABRT) && stderr.contains("memory allocation of") =>
+        {
+            Outcome::MemoryLimitExceeded
+        }
+        outcome => outcome,
+    };
+
+    Ok(RunResult { outcome, stdout, stderr, duration: start.elapsed() })
+}
+
+// Stops the running program, if any. Returns false when nothing was running.
+pub fn interrupt() -> bool {
+    let group = RUNNING_GROUP.load(Ordering::SeqCst);
+    if group == 0 {
+        return false;
+    }
+    INTERRUPTED.store(true, Ordering::SeqCst);
+    // SAFETY: plain syscall on the program's process group.
+    unsafe {
+        libc::kill(-group, libc::SIGKILL);
+    }
+    true
+}
+
+fn classify(status: ExitStatus) -> Outcome {
+    match status.signal() {
+        Some(libc::SIGXCPU) => Outcome::CpuLimitExceeded,
+        _ => Outcome::Exited(status),
+    }
+}
+
+fn kill_group(child: &mut Child) -> io::Result<()> {
+    // SAFETY: plain syscall; the child leads its own process group.
+    unsafe {
+        libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL);
+    }
+    child.wait().map(|_| ())
+}
+
+// Reads a pipe to the end on a background thread, keeping at most `limit`
+// bytes and raising `overflowed` once the program writes more than that.
+fn capture<R: Read + Send + 'static>(
+    pipe: Option<R>,
+    limit: usize,
+    overflowed: &Arc<AtomicBool>,
+) -> thread::JoinHandle<String> {
+    let overflowed = Arc::clone(overflowed);
+    thread::spawn(move || {
+        let mut kept = Vec::new();
+        let Some(mut pipe) = pipe else {
+            return String::new();
+        };
+        let mut buf = [0u8; 8192];
+        loop {
+            match pipe.read(&mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => {
+                    let room = limit.saturating_sub(kept.len());
+                    kept.extend_from_slice(&buf[..n.min(room)]);
+                    if n > room {
+                        overflowed.store(true, Ordering::Relaxed);
+                    }
+                }
+            }
+        }
+        String::from_utf8_lossy(&kept).into_owned()
+    })
+}
+
+// RLIMIT_NPROC that lets the program start `extra` processes or threads
+// beyond those the user already runs, which the limit counts too. Never
+// above the hard limit, which an unprivileged process can't raise. None
+// where /proc can't tell us what is running.
+#[cfg(target_os = "linux")]
+fn process_limit(extra: u64) -> Option<u64> {
+    // SAFETY: getuid has no preconditions
+    let uid = unsafe { libc::getuid() }.to_string();
+    let mut tasks = 0;
+    for entry in std::fs::read_dir("/proc").ok()?.flatten() {
+        // Processes can exit while we look; those just don't count
+        let Ok(status) = std::fs::read_to_string(entry.path().join("status")) else { continue };
+        let field = |name: &str| status.lines().find_map(|line| line.strip_prefix(name)).map(str::trim);
+        // "Uid:" lists the real, effective, saved and filesystem ids; the real one counts
+        if field("Uid:").and_then(|ids| ids.split_whitespace().next()) == Some(uid.as_str()) {
+            tasks += field("Threads:").and_then(|n| n.parse::<u64>().ok()).unwrap_or(1);
+        }
+    }
+
+    let mut hard = libc::rlimit { rlim_cur: 0, rlim_max: libc::RLIM_INFINITY };
+    // SAFETY: getrlimit only writes to `hard`
+    unsafe { libc::getrlimit(libc::RLIMIT_NPROC, &mut hard) };
+    Some((tasks + extra).min(hard.rlim_max))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn process_limit(_extra: u64) -> Option<u64> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+mod sandbox {
+    use std::ffi::CString;
+    use std::io;
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::Path;
+
+    const MOUNT_ATTR_RDONLY: u64 = 0x1;
+
+    // Mirrors `struct mount_attr` from <linux/mount.h>.
+    #[repr(C)]
+    struct MountAttr {
+        attr_set: u64,
+        attr_clr: u64,
+        propagation: u64,
+        userns_fd: u64,
+    }
+
+    // Everything the child needs, built before the fork so that `enter`
+    // does not allocate.
+    pub struct Sandbox {
+        scratch: CString,
+        uid_map: Vec<u8>,
+        gid_map: Vec<u8>,
+    }
+
+    pub fn prepare(scratch: &Path) -> io::Result<Sandbox> {
+        let scratch = scratch.canonicalize()?;
+        // SAFETY: getuid/getgid cannot fail.
+        let (uid, gid) = unsafe { (libc::getuid(), libc::getgid()) };
+        Ok(Sandbox {
+            scratch: CString::new(scratch.as_os_str().as_bytes())?,
+            uid_map: format!("{} {} 1\n", uid, uid).into_bytes(),
+            gid_map: format!("{} {} 1\n", gid, gid).into_bytes(),
+        })
+    }
+
+    impl Sandbox {
+        pub fn enter(&self) -> io::Result<()> {
+            // SAFETY: raw syscalls in the forked child on pre-built buffers.
+            unsafe {
+                check(libc::unshare(libc::CLONE_NEWUSER | libc::CLONE_NEWNS | libc::CLONE_NEWNET))?;
+                write_file(c"/proc/self/setgroups", b"deny")?;
+                write_file(c"/proc/self/uid_map", &self.uid_map)?;
+                write_file(c"/proc/self/gid_map", &self.gid_map)?;
+
+                // Keep our mount changes out of the parent namespace
+                check(libc::mount(
+                    std::ptr::null(),
+                    c"/".as_ptr(),
+                    std::ptr::null(),
+                    libc::MS_REC | libc::MS_PRIVATE,
+                    std::ptr::null(),
+                ))?;
+                // The scratch directory becomes its own mount, so it can stay writable
+                check(libc::mount(
+                    self.scratch.as_ptr(),
+                    self.scratch.as_ptr(),
+                    std::ptr::null(),
+                    libc::MS_BIND | libc::MS_REC,
+                    std::ptr::null(),
+                ))?;
+                set_readonly(c"/", true)?;
+                set_readonly(&self.scratch, false)?;
+                // The working directory still points below the old mount; re-enter
+                // it so relative paths land on the writable bind mount.
+                check(libc::chdir(self.scratch.as_ptr()))?;
+            }
+            Ok(())
+        }
+    }
+
+    unsafe fn set_readonly(path: &std::ffi::CStr, readonly: bool) -> io::Result<()> {
+        let attr = MountAttr {
+            attr_set: if readonly { MOUNT_ATTR_RDONLY } else { 0 },
+            attr_clr: if readonly { 0 } else { MOUNT_ATTR_RDONLY },
+            propagation: 0,
+            userns_fd: 0,
+        };
+        let rc = libc::syscall(
+            libc::SYS_mount_setattr,
+            libc::AT_FDCWD,
+            path.as_ptr(),
+            libc::AT_RECURSIVE,
+            &attr as *const MountAttr,
+            std::mem::size_of::<MountAttr>(),
+        );
+        check(rc as libc::c_int)
+    }
+
+    unsafe fn write_file(path: &std::ffi::CStr, contents: &[u8]) -> io::Result<()> {
+        let fd = libc::open(path.as_ptr(), libc::O_WRONLY | libc::O_CLOEXEC);
+        check(fd)?;
+        let written = libc::write(fd, contents.as_ptr().cast(), contents.len());
+        libc::close(fd);
+        if written < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    fn check(rc: libc::c_int) -> io::Result<()> {
+        if rc < 0 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(())
+        }
+    }
+}
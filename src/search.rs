@@ -0,0 +1,363 @@
+// `search <query>`: full-text search over every exercise's title, slug,
+// description, instructions, hint, starting code and solution. Each word
+// of the query has to appear somewhere in an exercise, as a whole word, the
+// start of one or inside one, in that order of preference. Matches in the
+// title count most and matches in code least. Results show the best
+// matching line with the query's words highlighted.
+
+use crate::style::{self, paint, BOLD, DIM, RESET, YELLOW};
+use crate::{slug, Chapter, Tutorial};
+use std::cmp::Reverse;
+use std::io::{self, Write};
+
+const MAX_RESULTS: usize = 10;
+const SNIPPET_WIDTH: usize = 72;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Field {
+    Title,
+    Slug,
+    Chapter,
+    Description,
+    Instructions,
+    Hint,
+    Code,
+    Solution,
+}
+
+impl Field {
+    fn weight(self) -> usize {
+        match self {
+            Field::Title => 10,
+            Field::Slug => 6,
+            Field::Chapter => 4,
+            Field::Description | Field::Instructions => 3,
+            Field::Hint => 2,
+            Field::Code | Field::Solution => 1,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Field::Title => "title",
+            Field::Slug => "slug",
+            Field::Chapter => "chapter",
+            Field::Description => "description",
+            Field::Instructions => "instructions",
+            Field::Hint => "hint",
+            Field::Code => "code",
+            Field::Solution => "solution",
+        }
+    }
+}
+
+struct Document {
+    chapter: usize,
+    exercise: usize,
+    // Each field's text with its words, lowercased
+    fields: Vec<(Field, String, Vec<String>)>,
+}
+
+// Built once per session; the exercises don't change while it runs
+pub struct Index {
+    documents: Vec<Document>,
+}
+
+pub struct Hit {
+    pub chapter: usize,
+    pub exercise: usize,
+    score: usize,
+    // Where the best match is, with the line it's on
+    field: Field,
+    line: String,
+}
+
+impl Index {
+    pub fn new(chapters: &[Chapter]) -> Self {
+        let mut documents = Vec::new();
+        for (i, chapter) in chapters.iter().enumerate() {
+            for (j, exercise) in chapter.exercises.iter().enumerate() {
+                let mut code = exercise.initial_code.clone();
+                for source in exercise.files.values() {
+                    code.push('\n');
+                    code.push_str(source);
+                }
+                let fields = [
+                    (Field::Title, exercise.title.clone()),
+                    (Field::Slug, slug::key(chapters, i, j)),
+                    (Field::Chapter, chapter.title.clone()),
+                    (Field::Description, exercise.description.clone()),
+                    (Field::Instructions, exercise.instructions.join("\n")),
+                    (Field::Hint, exercise.hint.clone().unwrap_or_default()),
+                    (Field::Code, code),
+                    (Field::Solution, exercise.solution.clone().unwrap_or_default()),
+                ];
+                let fields = fields.into_iter().filter(|(_, text)| !text.trim().is_empty()).map(|(field, text)| {
+                    let words = words(&text);
+                    (field, text, words)
+                });
+                documents.push(Document { chapter: i, exercise: j, fields: fields.collect() });
+            }
+        }
+        Self { documents }
+    }
+
+    // Best match first; ties keep the order of the exercises
+    pub fn search(&self, query: &str) -> Vec<Hit> {
+        let terms = words(query);
+        if terms.is_empty() {
+            return Vec::new();
+        }
+        let phrase = query.trim().to_ascii_lowercase();
+
+        let mut hits: Vec<Hit> = self.documents.iter().filter_map(|document| document.score(&terms, &phrase)).collect();
+        hits.sort_by_key(|hit| Reverse(hit.score));
+        hits
+    }
+}
+
+impl Document {
+    fn score(&self, terms: &[String], phrase: &str) -> Option<Hit> {
+        let mut total = 0;
+        let mut best: Option<(usize, Field, &str)> = None;
+        let mut found = vec![false; terms.len()];
+
+        for (field, text, words) in &self.fields {
+            let mut score = 0;
+            for (term, found) in terms.iter().zip(&mut found) {
+                // A word repeated over and over shouldn't drown out everything else
+                let matches: usize = words.iter().map(|word| word_score(term, word)).filter(|&s| s > 0).take(3).sum();
+                if matches > 0 {
+                    *found = true;
+                    score += matches;
+                }
+            }
+            if terms.len() > 1 && text.to_ascii_lowercase().contains(phrase) {
+                score += 5;
+            }
+            let score = score * field.weight();
+            total += score;
+            // The title and slug are shown with every result, so a line from elsewhere says more
+            let rank = if matches!(field, Field::Title | Field::Slug) { 0 } else { score };
+            if score > 0 && best.is_none_or(|(best, _, _)| rank > best) {
+                best = Some((rank, *field, text));
+            }
+        }
+
+        if found.contains(&false) {
+            return None;
+        }
+        let (_, field, text) = best?;
+        let line = text.lines().find(|line| terms.iter().any(|term| line.to_ascii_lowercase().contains(term.as_str())));
+        Some(Hit {
+            chapter: self.chapter,
+            exercise: self.exercise,
+            score: total,
+            field,
+            line: line.unwrap_or(text).trim().to_string(),
+        })
+    }
+}
+
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_ascii_lowercase())
+        .collect()
+}
+
+fn word_score(term: &str, word: &str) -> usize {
+    if word == term {
+        3
+    } else if word.starts_with(term) {
+        2
+    } else if word.contains(term) {
+        1
+    } else {
+        0
+    }
+}
+
+// The part of `line` around the first term, at most SNIPPET_WIDTH
+// characters, with every term highlighted
+fn snippet(line: &str, terms: &[String], color: bool) -> String {
+    let lower = line.to_ascii_lowercase();
+    let first = terms.iter().filter_map(|term| lower.find(term.as_str())).min().unwrap_or(0);
+    let chars: Vec<(usize, char)> = line.char_indices().collect();
+    let at = chars.iter().position(|&(i, _)| i >= first).unwrap_or(0);
+    let start = at.saturating_sub(SNIPPET_WIDTH / 3).min(chars.len().saturating_sub(SNIPPET_WIDTH));
+    let end = (start + SNIPPET_WIDTH).min(chars.len());
+    let byte = |i: usize| chars.get(i).map_or(line.len(), |&(byte, _)| byte);
+    let (from, to) = (byte(start), byte(end));
+
+    let mut out = String::new();
+    if start > 0 {
+        out.push('…');
+    }
+    let lower = &lower[from..to];
+    let mut pos = 0;
+    while pos < lower.len() {
+        let next = terms.iter().filter_map(|term| lower[pos..].find(term.as_str()).map(|i| (pos + i, term.len()))).min();
+        let Some((i, len)) = next else { break };
+        out.push_str(&line[from + pos..from + i]);
+        out.push_str(paint(color, BOLD));
+        out.push_str(paint(color, YELLOW));
+        out.push_str(&line[from + i..from + i + len]);
+        out.push_str(paint(color, RESET));
+        pos = i + len;
+    }
+    out.push_str(&line[from + pos..to]);
+    if end < chars.len() {
+        out.push('…');
+    }
+    out
+}
+
+impl Tutorial {
+    // Lists the matches and offers to go to one
+    pub(crate) fn search(&mut self, index: &Index, query: &str) {
+        let hits = index.search(query);
+        if hits.is_empty() {
+            println!("🔎 Nothing matches '{}'.", query);
+            return;
+        }
+
+        let color = style::enabled();
+        let terms = words(query);
+        println!("\n🔎 {} result(s) for '{}':", hits.len(), query);
+        for (n, hit) in hits.iter().take(MAX_RESULTS).enumerate() {
+            let exercise = &self.chapters[hit.chapter].exercises[hit.exercise];
+            println!(
+                "\n  {}. {}.{} {} {}({}){}",
+                n + 1,
+                hit.chapter + 1,
+                hit.exercise + 1,
+                exercise.title,
+                paint(color, DIM),
+                slug::key(&self.chapters, hit.chapter, hit.exercise),
+                paint(color, RESET)
+            );
+            if !matches!(hit.field, Field::Title | Field::Slug) {
+                println!("     {}: {}", hit.field.name(), snippet(&hit.line, &terms, color));
+            }
+        }
+        if hits.len() > MAX_RESULTS {
+            println!("\n   ...and {} more. Add words to narrow it down.", hits.len() - MAX_RESULTS);
+        }
+
+        let shown = hits.len().min(MAX_RESULTS);
+        print!("\nGo to which result? [1-{}, Enter to stay] ", shown);
+        io::stdout().flush().unwrap();
+        let mut input = String::new();
+        io::stdin().read_line(&mut input).unwrap();
+        let input = input.trim();
+        if input.is_empty() {
+            return;
+        }
+        match input.parse::<usize>().ok().filter(|n| (1..=shown).contains(n)) {
+            Some(n) => self.goto_exercise(hits[n - 1].chapter + 1, hits[n - 1].exercise + 1),
+            None => println!("❌ There is no result '{}'.", input),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Exercise;
+
+    fn chapters() -> Vec<Chapter> {
+        let exercise = |title: &str, slug: &str, description: &str| Exercise {
+            title: title.to_string(),
+            slug: slug.to_string(),
+            description: description.to_string(),
+            ..Default::default()
+        };
+        let mut chapters = vec![
+            Chapter {
+                title: "Flow of Control".to_string(),
+                slug: String::new(),
+                description: String::new(),
+                exercises: vec![
+                    exercise("Loops", "loop", "Repeat code with `loop`, `while` and `for`."),
+                    exercise("FizzBuzz", "fizzbuzz", "Print the numbers, with a closure for the rules."),
+                    exercise("Closures", "capturing", "Functions that capture their environment."),
+                ],
+            },
+            Chapter {
+                title: "Strings".to_string(),
+                slug: String::new(),
+                description: String::new(),
+                exercises: vec![exercise(
+                    "Unicode",
+                    "",
+                    "Strings are UTF-8: \"héllo wörld\" has 11 chars in 13 bytes, and each of «naïve», «café» and «crème brûlée» needs more bytes than letters, which is why slicing a String by byte offset can panic.",
+                )],
+            },
+        ];
+        slug::assign(&mut chapters);
+        chapters
+    }
+
+    fn found(index: &Index, query: &str) -> Vec<(usize, usize)> {
+        index.search(query).iter().map(|hit| (hit.chapter, hit.exercise)).collect()
+    }
+
+    #[test]
+    fn best_matches_first() {
+        let index = Index::new(&chapters());
+        // "Closures" in a title, "closure" in FizzBuzz's description
+        assert_eq!(found(&index, "closure"), [(0, 2), (0, 1)]);
+        // "capturing" is Closures' slug, "capture" is in its description too
+        assert_eq!(found(&index, "captur"), [(0, 2)]);
+        // The slug "loop" beats `loop` in the description
+        let hits = index.search("loop");
+        assert_eq!((hits[0].chapter, hits[0].exercise), (0, 0));
+        assert_eq!(hits[0].field, Field::Description);
+        // Every word has to match somewhere
+        assert!(found(&index, "closure zebra").is_empty());
+        assert!(index.search("  ").is_empty());
+    }
+
+    #[test]
+    fn title_beats_slug_beats_description() {
+        let mut chapters = chapters();
+        chapters[0].exercises[0].description = "You can't borrow it twice.".to_string();
+        chapters[0].exercises[1].slug = "borrow-rules".to_string();
+        chapters[0].exercises[2].title = "Borrowing".to_string();
+        slug::assign(&mut chapters);
+        let index = Index::new(&chapters);
+        assert_eq!(found(&index, "borrow"), [(0, 2), (0, 1), (0, 0)]);
+        let fields: Vec<Field> = index.search("borrow").iter().map(|hit| hit.field).collect();
+        assert_eq!(fields, [Field::Title, Field::Slug, Field::Description]);
+    }
+
+    #[test]
+    fn case_insensitive() {
+        let index = Index::new(&chapters());
+        assert_eq!(found(&index, "FIZZBUZZ"), found(&index, "fizzbuzz"));
+        assert_eq!(found(&index, "Utf"), [(1, 0)]);
+    }
+
+    #[test]
+    fn snippets_around_multibyte_characters() {
+        let chapters = chapters();
+        let index = Index::new(&chapters);
+        let hits = index.search("panic");
+        assert_eq!(hits.len(), 1);
+        let line = &hits[0].line;
+        let terms = words("panic");
+        let shown = snippet(line, &terms, false);
+        assert!(shown.starts_with('…'), "{}", shown);
+        assert!(shown.ends_with("can panic."), "{}", shown);
+        assert_eq!(shown.chars().count(), SNIPPET_WIDTH + 1);
+
+        // Cut right next to, and in the middle of, runs of two-byte characters
+        for term in ["naïve", "brûlée", "wörld", "héllo"] {
+            let terms = words(term);
+            let shown = snippet(line, &terms, true);
+            assert!(shown.contains(&format!("{}{}{}{}", BOLD, YELLOW, term, RESET)), "{}", shown);
+        }
+        assert_eq!(snippet("«café»", &words("café"), false), "«café»");
+    }
+}
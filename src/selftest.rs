@@ -0,0 +1,82 @@
+// `selftest`: checks the exercises themselves rather than the learner.
+// Every solution is run through the same compile/verify step as learner
+// code and must pass; with `--initial`, every exercise's starting code is
+// also run and must not pass already. Content pack authors can point
+// RUSTTUTOR_CONTENT at their pack to check it.
+
+use crate::cli::{self, FAILED};
+use crate::Tutorial;
+use std::process::ExitCode;
+
+struct Failure {
+    exercise: String,
+    // "solution" or "initial"
+    code: &'static str,
+    problem: String,
+}
+
+impl Tutorial {
+    pub(crate) fn selftest(&mut self, initial: bool) -> ExitCode {
+        let total: usize = self.chapters.iter().map(|c| c.exercises.len()).sum();
+        println!("\n🧪 Self-test: checking {} exercises...", total);
+
+        let mut failures = Vec::new();
+        let mut skipped = Vec::new();
+        for chapter in 0..self.chapters.len() {
+            for exercise in 0..self.chapters[chapter].exercises.len() {
+                self.current_chapter = chapter;
+                self.current_exercise = exercise;
+                let id = self.exercise_number();
+                let ex = &self.chapters[chapter].exercises[exercise];
+                let title = ex.title.clone();
+                let (solution, initial_code, files) = (ex.solution.clone(), ex.initial_code.clone(), ex.files.clone());
+
+                let mut checks = Vec::new();
+                match solution {
+                    Some(solution) => checks.push(("solution", solution, true)),
+                    None => skipped.push(id.clone()),
+                }
+                if initial {
+                    checks.push(("initial", initial_code, false));
+                }
+
+                let mut ok = true;
+                for (code, source, should_pass) in checks {
+                    self.current_code = source;
+                    self.current_files = files.clone();
+                    let passed = match cli::quietly(|| self.run_current_exercise()) {
+                        Ok(passed) => passed,
+                        Err(e) => {
+                            println!("❌ Could not run the self-test: {}", e);
+                            return ExitCode::from(FAILED);
+                        }
+                    };
+                    if passed != should_pass {
+                        ok = false;
+                        let problem = if should_pass { self.report.problem() } else { "already passes".to_string() };
+                        failures.push(Failure { exercise: id.clone(), code, problem });
+                    }
+                }
+                println!("   {} {} {}", if ok { "✅" } else { "❌" }, id, title);
+            }
+        }
+
+        if !skipped.is_empty() {
+            println!("\n⏭️  No solution to check for: {}", skipped.join(", "));
+        }
+        println!("\n{}", "─".repeat(60));
+        if failures.is_empty() {
+            println!("🎉 All {} exercises check out!", total);
+            return ExitCode::SUCCESS;
+        }
+
+        println!("❌ {} problem(s) found:\n", failures.len());
+        let width = failures.iter().map(|f| f.exercise.len()).max().unwrap_or(0).max("Exercise".len());
+        println!("   {:<width$}  {:<8}  Problem", "Exercise", "Code");
+        for failure in &failures {
+            let problem = failure.problem.lines().next().unwrap_or_default();
+            println!("   {:<width$}  {:<8}  {}", failure.exercise, failure.code, problem);
+        }
+        ExitCode::from(FAILED)
+    }
+}
@@ -0,0 +1,182 @@
+// The interactive session as a small state machine. Every step returns the
+// state to go to next, so commands at the prompt only leave it on purpose:
+// `edit` opens the editor, moving to another exercise shows that exercise,
+// and everything else comes straight back to the prompt.
+//
+// Guided mode (`--guided`, `guided = true` in the config, or `guide` at the
+// prompt) walks through each exercise the way vimtutor does: title screen,
+// description, editor, then a menu of what to do next.
+
+use crate::prompt::Prompt;
+use crate::search::Index;
+use crate::slug;
+use crate::Tutorial;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, PartialEq)]
+enum State {
+    // The welcome screen
+    Intro,
+    // Showing the current exercise
+    Reading,
+    // Editing the current exercise, or one file of it
+    Editing(Option<String>),
+    // Back from the editor, with the choices of what to do next
+    Reviewing,
+    // Waiting at the `rusttutor>` prompt
+    Command,
+    Done,
+}
+
+struct Session {
+    prompt: Prompt,
+    guided: bool,
+    // Built the first time the learner searches
+    index: Option<Index>,
+}
+
+impl Tutorial {
+    pub(crate) fn run(&mut self, aliases: BTreeMap<String, String>, guided: bool) {
+        let prompt = match Prompt::new(self.exercise_names(), aliases) {
+            Ok(prompt) => prompt,
+            Err(e) => {
+                println!("❌ Could not set up the command prompt: {}", e);
+                return;
+            }
+        };
+        let mut session = Session { prompt, guided, index: None };
+
+        let mut state = State::Intro;
+        while state != State::Done {
+            state = self.step(state, &mut session);
+        }
+    }
+
+    fn step(&mut self, state: State, session: &mut Session) -> State {
+        match state {
+            State::Intro => {
+                self.print_welcome(session.guided);
+                State::Reading
+            }
+            State::Reading if session.guided => {
+                self.display_chapter_and_exercise();
+                self.wait_for_enter("Press Enter to see the description and instructions...");
+                self.display_description_and_instructions();
+                self.wait_for_enter("Press Enter to open the code in your editor...");
+                State::Editing(None)
+            }
+            State::Reading => {
+                println!();
+                self.print_exercise();
+                println!("\n✏️  Type 'edit' to write your code and 'run' to check it.");
+                State::Command
+            }
+            State::Editing(file) => {
+                self.edit_exercise(file.as_deref());
+                if session.guided {
+                    State::Reviewing
+                } else {
+                    State::Command
+                }
+            }
+            State::Reviewing => {
+                self.post_edit_options();
+                State::Command
+            }
+            State::Command => {
+                println!();
+                match session.prompt.read("rusttutor> ") {
+                    Some(input) => self.command(&input, session),
+                    None => self.quit(),
+                }
+            }
+            State::Done => State::Done,
+        }
+    }
+
+    fn print_welcome(&self, guided: bool) {
+        println!("\x1b[2J\x1b[H"); // Clear screen
+        println!("🦀 Welcome to RustTutor - Interactive Rust Learning!");
+        println!("=================================================");
+        println!("Based on Rust by Example (https://doc.rust-lang.org/rust-by-example/)");
+        println!("Inspired by vimtutor\n");
+
+        if guided {
+            println!("This is an interactive tutorial. You'll be guided through each exercise step by step.");
+            self.wait_for_enter("Press Enter to start your first exercise...");
+        } else {
+            println!("Type 'help' for commands, or 'guide' to be walked through each exercise step by step.");
+        }
+    }
+
+    // Runs one command from the prompt and says where the session goes next
+    fn command(&mut self, input: &str, session: &mut Session) -> State {
+        let position = (self.current_chapter, self.current_exercise);
+        match input {
+            "q" | "quit" => return self.quit(),
+            "edit" => return State::Editing(None),
+            cmd if cmd.starts_with("edit ") => return State::Editing(Some(cmd["edit ".len()..].trim().to_string())),
+            "restart" => {
+                self.restart_exercise();
+                return State::Reading;
+            }
+            "guide" => {
+                session.guided = !session.guided;
+                if !session.guided {
+                    println!("🧭 Guided mode is off: you stay at the prompt until you move on.");
+                    return State::Command;
+                }
+                println!("🧭 Guided mode is on: each exercise is shown step by step.");
+                return State::Reading;
+            }
+            "h" | "help" => self.print_help(),
+            "l" | "list" => self.list_chapters(),
+            "r" | "run" | "" => {
+                self.run_current_exercise();
+            }
+            "w" | "watch" => self.watch_exercise(),
+            "tui" => self.run_tui(),
+            "s" | "solution" => self.show_solution(),
+            "hint" => self.show_hint(),
+            "n" | "next" => self.next_exercise(),
+            "p" | "prev" => self.prev_exercise(),
+            "reset" => self.reset_exercise(),
+            "backtrace" => self.toggle_backtrace(),
+            "workspace" => println!("📁 Workspace: {}", self.workspace.path().display()),
+            cmd if cmd.starts_with("explain ") => self.explain_error(&cmd["explain ".len()..]),
+            "search" => println!("Usage: search <words>, e.g. search fizzbuzz"),
+            cmd if cmd.starts_with("search ") => {
+                let index = session.index.get_or_insert_with(|| Index::new(&self.chapters));
+                self.search(index, cmd["search ".len()..].trim());
+            }
+            cmd if cmd.starts_with("goto ") => {
+                let query = cmd["goto ".len()..].trim();
+                // `goto 3 1` still works; anything else is a number, slug or title
+                let numbers = query
+                    .split_once(' ')
+                    .and_then(|(chapter, exercise)| Some((chapter.parse::<usize>().ok()?, exercise.trim().parse::<usize>().ok()?)));
+                match numbers {
+                    Some((chapter, exercise)) => self.goto_exercise(chapter, exercise),
+                    None => match slug::search(&self.chapters, query) {
+                        Ok((chapter, exercise)) => self.goto_exercise(chapter + 1, exercise + 1),
+                        Err(e) => println!("❌ {}", e),
+                    },
+                }
+            }
+            _ => println!("Unknown command. Type 'h' for help."),
+        }
+
+        // The full-screen view shows exercises itself, so coming back from it doesn't
+        if input != "tui" && position != (self.current_chapter, self.current_exercise) {
+            State::Reading
+        } else {
+            State::Command
+        }
+    }
+
+    fn quit(&mut self) -> State {
+        self.save_progress();
+        println!("Happy coding with Rust! 🦀");
+        State::Done
+    }
+}
@@ -0,0 +1,224 @@
+// Stable names for chapters and exercises. An exercise is known by its
+// chapter's slug and its own, e.g. `custom-types/structures`, and progress
+// is keyed by that, so adding or reordering content never attaches saved
+// code to the wrong exercise. A content pack can set `slug` explicitly;
+// otherwise it comes from the file name without its numeric prefix, and
+// for the built-in exercises from the title.
+
+use crate::Chapter;
+use std::collections::BTreeSet;
+
+// "Custom Types" -> "custom-types"
+pub fn slugify(text: &str) -> String {
+    let slug = text
+        .to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    if slug.is_empty() { "untitled".to_string() } else { slug }
+}
+
+// "03-custom-types" -> "custom-types"
+pub fn from_file_name(stem: &str) -> String {
+    let name = stem.trim_start_matches(|c: char| c.is_ascii_digit());
+    let name = if name.len() < stem.len() { name.trim_start_matches(['-', '_']) } else { name };
+    slugify(if name.is_empty() { stem } else { name })
+}
+
+// Fills in missing slugs from titles and makes them unique: a second
+// chapter or exercise with the same slug gets `-2` appended, and so on.
+pub fn assign(chapters: &mut [Chapter]) {
+    let mut chapter_slugs = BTreeSet::new();
+    for chapter in chapters {
+        let slug = if chapter.slug.is_empty() { &chapter.title } else { &chapter.slug };
+        chapter.slug = unique(slugify(slug), &mut chapter_slugs);
+
+        let mut exercise_slugs = BTreeSet::new();
+        for exercise in &mut chapter.exercises {
+            let slug = if exercise.slug.is_empty() { &exercise.title } else { &exercise.slug };
+            exercise.slug = unique(slugify(slug), &mut exercise_slugs);
+        }
+    }
+}
+
+fn unique(slug: String, taken: &mut BTreeSet<String>) -> String {
+    let mut candidate = slug.clone();
+    let mut n = 1;
+    while taken.contains(&candidate) {
+        n += 1;
+        candidate = format!("{}-{}", slug, n);
+    }
+    taken.insert(candidate.clone());
+    candidate
+}
+
+pub fn key(chapters: &[Chapter], chapter: usize, exercise: usize) -> String {
+    format!("{}/{}", chapters[chapter].slug, chapters[chapter].exercises[exercise].slug)
+}
+
+// Finds an exercise by its number ("2.1"), full slug
+// ("primitives/scalar-types") or, if only one exercise has it, its own
+// slug ("scalar-types")
+pub fn find(chapters: &[Chapter], id: &str) -> Option<(usize, usize)> {
+    if let Some((chapter, exercise)) = id.split_once('.') {
+        if let (Ok(chapter), Ok(exercise)) = (chapter.parse::<usize>(), exercise.parse::<usize>()) {
+            let exists = chapter > 0 && exercise > 0 && chapters.get(chapter - 1).is_some_and(|c| exercise <= c.exercises.len());
+            return exists.then(|| (chapter - 1, exercise - 1));
+        }
+    }
+
+    let mut matches = positions(chapters).filter(|&(i, j)| {
+        let exercise = &chapters[i].exercises[j].slug;
+        id == key(chapters, i, j) || id == exercise
+    });
+    match (matches.next(), matches.next()) {
+        (Some(found), None) => Some(found),
+        _ => None,
+    }
+}
+
+// Like `find`, but also takes a title or a rough spelling of a slug or
+// title, e.g. "struct" or "scalr". When several exercises match equally
+// well the error lists them.
+pub fn search(chapters: &[Chapter], query: &str) -> Result<(usize, usize), String> {
+    if let Some(found) = find(chapters, query) {
+        return Ok(found);
+    }
+
+    let query = query.to_lowercase();
+    let scored: Vec<((usize, usize), usize)> = positions(chapters)
+        .filter_map(|(i, j)| {
+            let exercise = &chapters[i].exercises[j];
+            let names = [key(chapters, i, j), exercise.slug.clone(), exercise.title.to_lowercase()];
+            names.iter().filter_map(|name| score(&query, name)).min().map(|score| ((i, j), score))
+        })
+        .collect();
+    let Some(best) = scored.iter().map(|&(_, score)| score).min() else {
+        return Err(format!("No exercise matches '{}'. Type 'list' to see them all.", query));
+    };
+    let best: Vec<(usize, usize)> = scored.iter().filter(|&&(_, score)| score == best).map(|&(found, _)| found).collect();
+    match best.as_slice() {
+        [found] => Ok(*found),
+        _ => {
+            let names: Vec<String> = best.iter().map(|&(i, j)| key(chapters, i, j)).collect();
+            Err(format!("'{}' could be any of: {}", query, names.join(", ")))
+        }
+    }
+}
+
+fn positions(chapters: &[Chapter]) -> impl Iterator<Item = (usize, usize)> + '_ {
+    chapters.iter().enumerate().flat_map(|(i, chapter)| (0..chapter.exercises.len()).map(move |j| (i, j)))
+}
+
+// How well `query` matches `name`, lower is better; None when it doesn't.
+// Exact beats prefix beats substring beats the query's letters appearing
+// in order, and fewer letters skipped in between is better.
+pub fn score(query: &str, name: &str) -> Option<usize> {
+    if query.is_empty() {
+        return None;
+    }
+    if name == query {
+        return Some(0);
+    }
+    if name.starts_with(query) {
+        return Some(1);
+    }
+    if name.contains(query) {
+        return Some(2);
+    }
+
+    let mut skipped = 0;
+    let mut chars = name.chars();
+    for wanted in query.chars() {
+        loop {
+            let c = chars.next()?;
+            if c == wanted {
+                break;
+            }
+            skipped += 1;
+        }
+    }
+    Some(3 + skipped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Exercise;
+
+    fn chapter(title: &str, exercises: &[&str]) -> Chapter {
+        Chapter {
+            title: title.to_string(),
+            slug: String::new(),
+            description: String::new(),
+            exercises: exercises.iter().map(|title| Exercise { title: title.to_string(), ..Default::default() }).collect(),
+        }
+    }
+
+    fn chapters() -> Vec<Chapter> {
+        let mut chapters = vec![
+            chapter("Hello World", &["Basic", "Comments", "Formatted print"]),
+            chapter("Primitives", &["Scalar Types", "Tuples", "Arrays and Slices"]),
+            chapter("Custom Types", &["Structures", "Enums", "Enums"]),
+            chapter("Conversion", &["From and Into", "Formatted print"]),
+        ];
+        assign(&mut chapters);
+        chapters
+    }
+
+    #[test]
+    fn slugs() {
+        assert_eq!(slugify("Custom Types"), "custom-types");
+        assert_eq!(slugify("From and Into: `String`s!"), "from-and-into-string-s");
+        assert_eq!(slugify("?!"), "untitled");
+        assert_eq!(from_file_name("03-custom-types"), "custom-types");
+        assert_eq!(from_file_name("2_scalar_types"), "scalar-types");
+        assert_eq!(from_file_name("2048"), "2048");
+
+        let chapters = chapters();
+        assert_eq!(key(&chapters, 2, 2), "custom-types/enums-2");
+        assert_eq!(key(&chapters, 3, 0), "conversion/from-and-into");
+    }
+
+    #[test]
+    fn find_by_number_or_slug() {
+        let chapters = chapters();
+        assert_eq!(find(&chapters, "2.1"), Some((1, 0)));
+        assert_eq!(find(&chapters, "4.2"), Some((3, 1)));
+        assert_eq!(find(&chapters, "4.3"), None);
+        assert_eq!(find(&chapters, "0.1"), None);
+        assert_eq!(find(&chapters, "primitives/tuples"), Some((1, 1)));
+        assert_eq!(find(&chapters, "scalar-types"), Some((1, 0)));
+        // Two chapters have a "formatted-print"
+        assert_eq!(find(&chapters, "formatted-print"), None);
+        assert_eq!(find(&chapters, "conversion/formatted-print"), Some((3, 1)));
+        assert_eq!(find(&chapters, "Scalar Types"), None);
+    }
+
+    #[test]
+    fn search_by_rough_name() {
+        let chapters = chapters();
+        assert_eq!(search(&chapters, "2.2"), Ok((1, 1)));
+        assert_eq!(search(&chapters, "Scalar Types"), Ok((1, 0)));
+        assert_eq!(search(&chapters, "struct"), Ok((2, 0)));
+        assert_eq!(search(&chapters, "scalr"), Ok((1, 0)));
+        assert_eq!(search(&chapters, "arrays slices"), Ok((1, 2)));
+        assert_eq!(
+            search(&chapters, "formatted"),
+            Err("'formatted' could be any of: hello-world/formatted-print, conversion/formatted-print".to_string())
+        );
+        assert_eq!(search(&chapters, "xyzzy"), Err("No exercise matches 'xyzzy'. Type 'list' to see them all.".to_string()));
+    }
+
+    #[test]
+    fn scores() {
+        assert_eq!(score("tuples", "tuples"), Some(0));
+        assert_eq!(score("tup", "tuples"), Some(1));
+        assert_eq!(score("ple", "tuples"), Some(2));
+        assert_eq!(score("tpl", "tuples"), Some(4));
+        assert_eq!(score("tls", "tuples"), Some(6));
+        assert_eq!(score("selput", "tuples"), None);
+        assert_eq!(score("", "tuples"), None);
+    }
+}
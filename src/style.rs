@@ -0,0 +1,22 @@
+// ANSI colors for terminal output, switched off when NO_COLOR is set
+// (https://no-color.org).
+
+use std::env;
+
+pub const RED: &str = "\x1b[31m";
+pub const GREEN: &str = "\x1b[32m";
+pub const YELLOW: &str = "\x1b[33m";
+pub const BLUE: &str = "\x1b[34m";
+pub const BOLD: &str = "\x1b[1m";
+pub const DIM: &str = "\x1b[2m";
+pub const RED_BG: &str = "\x1b[41;97m";
+pub const GREEN_BG: &str = "\x1b[42;30m";
+pub const RESET: &str = "\x1b[0m";
+
+pub fn enabled() -> bool {
+    env::var_os("NO_COLOR").is_none()
+}
+
+pub fn paint(color: bool, code: &'static str) -> &'static str {
+    if color { code } else { "" }
+}
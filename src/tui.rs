@@ -0,0 +1,355 @@
+// Full-screen interface: the exercise tree next to the exercise's
+// description, a read-only view of the code and the output of the last
+// command, all on screen at once. Keys map onto the prompt's commands, and
+// what those commands print is captured and shown in the output pane.
+// Editing and watch mode hand the terminal back while they run.
+
+use crate::cli;
+use crate::slug;
+use crate::Tutorial;
+use ansi_to_tui::IntoText;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::crossterm::execute;
+use ratatui::crossterm::terminal::{self, EnterAlternateScreen};
+use ratatui::layout::{Constraint, Flex, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{Block, Clear, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use std::io;
+
+const KEYS: &[(&str, &str)] = &[
+    ("↑ ↓  j k", "Move through the exercises, or scroll the focused pane"),
+    ("Enter", "Open the highlighted exercise"),
+    ("Tab", "Focus the next pane"),
+    ("PgUp PgDn", "Scroll the focused pane by a page"),
+    ("r", "Run the exercise"),
+    ("e", "Edit the code in your editor"),
+    ("w", "Watch mode"),
+    ("n  p", "Next / previous exercise"),
+    ("h", "Show a hint"),
+    ("s", "Show the solution"),
+    ("x", "Reset the code to the starting code"),
+    ("b", "Turn panic backtraces on or off"),
+    ("?", "Show or hide this help"),
+    ("q  Esc", "Leave the full-screen view"),
+];
+
+#[derive(Clone, Copy, PartialEq)]
+enum Pane {
+    Tree,
+    Description,
+    Code,
+    Output,
+}
+
+impl Pane {
+    fn next(self) -> Self {
+        match self {
+            Self::Tree => Self::Description,
+            Self::Description => Self::Code,
+            Self::Code => Self::Output,
+            Self::Output => Self::Tree,
+        }
+    }
+}
+
+struct Ui {
+    // Rows of the tree: a chapter, or one of its exercises
+    rows: Vec<(usize, Option<usize>)>,
+    tree: ListState,
+    focus: Pane,
+    description: Text<'static>,
+    output: Text<'static>,
+    // Scroll offsets of the description, code and output panes
+    scroll: [u16; 3],
+    // Height of the last drawn panes, for paging
+    page: u16,
+    help: bool,
+}
+
+impl Ui {
+    fn selected(&self) -> Option<(usize, usize)> {
+        let (chapter, exercise) = self.rows.get(self.tree.selected()?)?;
+        Some((*chapter, (*exercise)?))
+    }
+
+    // Moves the highlight to the next exercise up or down, skipping chapters
+    fn move_selection(&mut self, down: bool) {
+        let current = self.tree.selected().unwrap_or(0);
+        let candidates: Box<dyn Iterator<Item = usize>> =
+            if down { Box::new(current + 1..self.rows.len()) } else { Box::new((0..current).rev()) };
+        if let Some(row) = candidates.into_iter().find(|&row| self.rows[row].1.is_some()) {
+            self.tree.select(Some(row));
+        }
+    }
+
+    fn scroll(&mut self, by: i32) {
+        let index = match self.focus {
+            Pane::Tree => return,
+            Pane::Description => 0,
+            Pane::Code => 1,
+            Pane::Output => 2,
+        };
+        let scroll = &mut self.scroll[index];
+        *scroll = (*scroll as i32 + by).clamp(0, u16::MAX as i32) as u16;
+    }
+
+    fn show_output(&mut self, output: &str) {
+        self.output = ansi_text(output.trim_matches('\n'));
+        self.scroll[2] = 0;
+    }
+}
+
+impl Tutorial {
+    pub(crate) fn run_tui(&mut self) {
+        let mut terminal = match ratatui::try_init() {
+            Ok(terminal) => terminal,
+            Err(e) => {
+                println!("❌ Could not start the full-screen view: {}", e);
+                return;
+            }
+        };
+        let result = self.tui_loop(&mut terminal);
+        ratatui::restore();
+        if let Err(e) = result {
+            println!("❌ The full-screen view failed: {}", e);
+        }
+    }
+
+    fn tui_loop(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        let rows = self
+            .chapters
+            .iter()
+            .enumerate()
+            .flat_map(|(i, chapter)| std::iter::once((i, None)).chain((0..chapter.exercises.len()).map(move |j| (i, Some(j)))))
+            .collect();
+        let mut ui = Ui {
+            rows,
+            tree: ListState::default(),
+            focus: Pane::Tree,
+            description: Text::default(),
+            output: Text::from("Press r to run your code, e to edit it, or ? for all keys."),
+            scroll: [0; 3],
+            page: 10,
+            help: false,
+        };
+        self.tui_exercise_changed(&mut ui)?;
+
+        loop {
+            terminal.draw(|frame| self.draw_tui(frame, &mut ui))?;
+            let Event::Key(key) = event::read()? else { continue };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            if ui.help {
+                ui.help = false;
+                continue;
+            }
+
+            let ctrl_c = key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL);
+            if ctrl_c || matches!(key.code, KeyCode::Char('q') | KeyCode::Esc) {
+                self.save_progress();
+                return Ok(());
+            }
+
+            let position = (self.current_chapter, self.current_exercise);
+            match key.code {
+                KeyCode::Char('?') => ui.help = true,
+                KeyCode::Tab => ui.focus = ui.focus.next(),
+                KeyCode::Up | KeyCode::Char('k') if ui.focus == Pane::Tree => ui.move_selection(false),
+                KeyCode::Down | KeyCode::Char('j') if ui.focus == Pane::Tree => ui.move_selection(true),
+                KeyCode::Up | KeyCode::Char('k') => ui.scroll(-1),
+                KeyCode::Down | KeyCode::Char('j') => ui.scroll(1),
+                KeyCode::PageUp => ui.scroll(-(ui.page as i32)),
+                KeyCode::PageDown => ui.scroll(ui.page as i32),
+                KeyCode::Enter => {
+                    if let Some((chapter, exercise)) = ui.selected() {
+                        self.save_progress();
+                        self.current_chapter = chapter;
+                        self.current_exercise = exercise;
+                        self.load_exercise_code();
+                        self.save_progress();
+                    }
+                }
+                KeyCode::Char('r') => {
+                    ui.show_output("🔧 Compiling and running your code...");
+                    terminal.draw(|frame| self.draw_tui(frame, &mut ui))?;
+                    // Out of raw mode, so Ctrl-C stops a runaway program as usual
+                    terminal::disable_raw_mode()?;
+                    let output = self.tui_capture(|tutorial| {
+                        tutorial.run_current_exercise();
+                    });
+                    terminal::enable_raw_mode()?;
+                    terminal.clear()?;
+                    ui.show_output(&output?);
+                }
+                KeyCode::Char('e') => {
+                    let before = (self.current_code.clone(), self.current_files.clone());
+                    suspended(terminal, || self.edit_exercise(None))?;
+                    if before == (self.current_code.clone(), self.current_files.clone()) {
+                        ui.show_output("ℹ️  No changes were made to the code.");
+                    } else {
+                        ui.show_output("✏️  Your code was updated. Press r to run it.");
+                    }
+                }
+                KeyCode::Char('w') => {
+                    suspended(terminal, || self.watch_exercise())?;
+                    ui.show_output("👋 Left watch mode.");
+                }
+                KeyCode::Char(key) => {
+                    let command: fn(&mut Self) = match key {
+                        'n' => Self::next_exercise,
+                        'p' => Self::prev_exercise,
+                        'h' => |tutorial| tutorial.show_hint(),
+                        's' => |tutorial| tutorial.show_solution(),
+                        'x' => Self::reset_exercise,
+                        'b' => Self::toggle_backtrace,
+                        _ => continue,
+                    };
+                    let output = self.tui_capture(command)?;
+                    ui.show_output(&output);
+                }
+                _ => {}
+            }
+
+            if position != (self.current_chapter, self.current_exercise) {
+                self.tui_exercise_changed(&mut ui)?;
+            }
+        }
+    }
+
+    // Runs a command, returning what it printed
+    fn tui_capture(&mut self, command: impl FnOnce(&mut Self)) -> io::Result<String> {
+        let path = self.workspace.output_file();
+        cli::capture(&path, || command(self)).map(|((), output)| output)
+    }
+
+    fn tui_exercise_changed(&mut self, ui: &mut Ui) -> io::Result<()> {
+        let position = (self.current_chapter, Some(self.current_exercise));
+        ui.tree.select(ui.rows.iter().position(|row| *row == position));
+        let description = self.tui_capture(|tutorial| {
+            println!("📚 {}\n", tutorial.chapters[tutorial.current_chapter].description);
+            tutorial.print_exercise();
+        })?;
+        // The pane's title already names the exercise
+        let description: Vec<&str> =
+            description.lines().filter(|line| line.is_empty() || !line.chars().all(|c| c == '=')).collect();
+        ui.description = ansi_text(&description.join("\n"));
+        ui.scroll = [0, 0, ui.scroll[2]];
+        Ok(())
+    }
+
+    fn draw_tui(&self, frame: &mut Frame, ui: &mut Ui) {
+        let [body, status] = Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+        let [sidebar, main] = Layout::horizontal([Constraint::Length(34), Constraint::Min(0)]).areas(body);
+        let [description, code, output] =
+            Layout::vertical([Constraint::Percentage(35), Constraint::Percentage(30), Constraint::Percentage(35)]).areas(main);
+        ui.page = output.height.saturating_sub(2).max(1);
+
+        let block = |pane: Pane, title: String| {
+            let style = if ui.focus == pane { Style::new().fg(Color::Yellow) } else { Style::new() };
+            Block::bordered().title(title).border_style(style)
+        };
+
+        let items: Vec<ListItem> = ui
+            .rows
+            .iter()
+            .map(|&(i, exercise)| match exercise {
+                None => ListItem::new(Line::styled(
+                    format!("{} {}", i + 1, self.chapters[i].title),
+                    Style::new().add_modifier(Modifier::BOLD),
+                )),
+                Some(j) => {
+                    let id = format!("{}.{}", i + 1, j + 1);
+                    let marker = if (i, j) == (self.current_chapter, self.current_exercise) {
+                        "▶"
+                    } else if self.progress.passed.contains(&slug::key(&self.chapters, i, j)) {
+                        "✓"
+                    } else {
+                        " "
+                    };
+                    ListItem::new(format!(" {} {} {}", marker, id, self.chapters[i].exercises[j].title))
+                }
+            })
+            .collect();
+        let list = List::new(items)
+            .block(block(Pane::Tree, " Exercises ".to_string()))
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, sidebar, &mut ui.tree);
+
+        let exercise = &self.chapters[self.current_chapter].exercises[self.current_exercise];
+        let title = format!(" {} {} ", self.exercise_number(), exercise.title);
+        let paragraph = Paragraph::new(ui.description.clone())
+            .block(block(Pane::Description, title))
+            .wrap(Wrap { trim: false })
+            .scroll((ui.scroll[0], 0));
+        frame.render_widget(paragraph, description);
+
+        let paragraph = Paragraph::new(self.code_text()).block(block(Pane::Code, " Code ".to_string())).scroll((ui.scroll[1], 0));
+        frame.render_widget(paragraph, code);
+
+        let paragraph = Paragraph::new(ui.output.clone())
+            .block(block(Pane::Output, " Output ".to_string()))
+            .wrap(Wrap { trim: false })
+            .scroll((ui.scroll[2], 0));
+        frame.render_widget(paragraph, output);
+
+        let backtrace = if self.backtrace { "  backtraces on" } else { "" };
+        let keys = format!(" r run  e edit  n/p next/prev  h hint  s solution  Tab focus  ? help  q quit{}", backtrace);
+        frame.render_widget(Line::styled(keys, Style::new().add_modifier(Modifier::REVERSED)), status);
+
+        if ui.help {
+            draw_help(frame);
+        }
+    }
+
+    // The learner's files with line numbers, main.rs first
+    fn code_text(&self) -> Text<'static> {
+        let mut files = vec![("main.rs", self.current_code.as_str())];
+        files.extend(self.current_files.iter().map(|(name, code)| (name.as_str(), code.as_str())));
+        let multiple = files.len() > 1;
+        let mut lines = Vec::new();
+        for (name, code) in files {
+            if multiple {
+                lines.push(Line::styled(format!("── {} ──", name), Style::new().add_modifier(Modifier::BOLD)));
+            }
+            for (i, line) in code.lines().enumerate() {
+                lines.push(Line::from(vec![
+                    Span::styled(format!("{:>4} ", i + 1), Style::new().add_modifier(Modifier::DIM)),
+                    Span::raw(line.replace('\t', "    ")),
+                ]));
+            }
+        }
+        Text::from(lines)
+    }
+}
+
+// Hands the terminal back for `f`, e.g. to run an editor in it
+fn suspended(terminal: &mut DefaultTerminal, f: impl FnOnce()) -> io::Result<()> {
+    ratatui::restore();
+    f();
+    terminal::enable_raw_mode()?;
+    execute!(io::stdout(), EnterAlternateScreen)?;
+    terminal.clear()
+}
+
+fn draw_help(frame: &mut Frame) {
+    let width = KEYS.iter().map(|(key, action)| key.chars().count() + action.chars().count() + 4).max().unwrap_or(0) as u16 + 2;
+    let [area] = Layout::horizontal([Constraint::Length(width)]).flex(Flex::Center).areas(frame.area());
+    let [area] = Layout::vertical([Constraint::Length(KEYS.len() as u16 + 2)]).flex(Flex::Center).areas(area);
+    let lines: Vec<Line> = KEYS
+        .iter()
+        .map(|(key, action)| {
+            Line::from(vec![Span::styled(format!(" {:<10}", key), Style::new().add_modifier(Modifier::BOLD)), Span::raw(*action)])
+        })
+        .collect();
+    frame.render_widget(Clear, area);
+    frame.render_widget(Paragraph::new(lines).block(Block::bordered().title(" Keys ")), area);
+}
+
+// Printed output, colors and all; other escape codes are dropped
+fn ansi_text(output: &str) -> Text<'static> {
+    let output = output.replace("\x1b[2J", "").replace("\x1b[H", "");
+    output.into_text().unwrap_or_else(|_| Text::raw(output))
+}
@@ -0,0 +1,130 @@
+// Watch mode: the exercise lives in the workspace file, the learner edits it
+// in any editor they like, and every save re-runs the compile/verify step.
+// Passing an exercise moves straight on to the next one.
+
+use crate::Tutorial;
+use notify::{RecursiveMode, Watcher};
+use std::io;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+enum WatchEvent {
+    Changed,
+    Stop,
+}
+
+impl Tutorial {
+    pub(crate) fn watch_exercise(&mut self) {
+        let mut source_file = match self.write_sources() {
+            Ok(source_file) => source_file,
+            Err(e) => {
+                println!("❌ Error writing to temporary file: {}", e);
+                return;
+            }
+        };
+
+        let (tx, rx) = mpsc::channel();
+        let file_tx = tx.clone();
+        // Any Rust file may be one of the exercise's; whether anything really
+        // changed is checked against the code when reading the files back
+        let watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            let Ok(event) = event else { return };
+            let ours = event.paths.iter().any(|path| path.extension().is_some_and(|ext| ext == "rs"));
+            if ours && (event.kind.is_modify() || event.kind.is_create()) {
+                let _ = file_tx.send(WatchEvent::Changed);
+            }
+        });
+        // Watch the directory rather than the file: many editors save by
+        // writing a new file and renaming it over the old one
+        let mut watcher = match watcher {
+            Ok(watcher) => watcher,
+            Err(e) => {
+                println!("❌ Could not start watching files: {}", e);
+                return;
+            }
+        };
+        // Recursive, so it also sees the src/ directory of Cargo projects
+        if let Err(e) = watcher.watch(self.workspace.path(), RecursiveMode::Recursive) {
+            println!("❌ Could not watch {}: {}", self.workspace.path().display(), e);
+            return;
+        }
+
+        // Reading stdin can't be interrupted, so watch mode is only left once
+        // this thread has read its line; otherwise it would swallow the next
+        // command typed at the prompt
+        let reader = thread::spawn(move || {
+            let mut input = String::new();
+            let _ = io::stdin().read_line(&mut input);
+            let _ = tx.send(WatchEvent::Stop);
+        });
+
+        self.print_watch_banner();
+        self.run_current_exercise();
+
+        while let Ok(event) = rx.recv() {
+            if let WatchEvent::Stop = event {
+                break;
+            }
+
+            // A single save usually fires several events; let them settle
+            thread::sleep(Duration::from_millis(100));
+            if rx.try_iter().any(|event| matches!(event, WatchEvent::Stop)) {
+                break;
+            }
+
+            // Skip our own writes and the empty file some editors leave mid-save
+            let (code, files) = match self.read_sources(&source_file) {
+                Ok((code, files)) if code.trim().is_empty() || files.values().any(|file| file.trim().is_empty()) => continue,
+                Ok((code, files)) if code != self.current_code || files != self.current_files => (code, files),
+                _ => continue,
+            };
+            self.current_code = code;
+            self.current_files = files;
+            self.save_progress();
+
+            println!("\x1b[2J\x1b[H"); // Clear screen
+            self.print_watch_banner();
+            if !self.run_current_exercise() {
+                continue;
+            }
+
+            let position = (self.current_chapter, self.current_exercise);
+            self.next_exercise();
+            if position == (self.current_chapter, self.current_exercise) {
+                println!("\nPress Enter to leave watch mode.");
+                continue;
+            }
+            source_file = match self.write_sources() {
+                Ok(source_file) => source_file,
+                Err(e) => {
+                    println!("❌ Error writing to temporary file: {}", e);
+                    println!("\nPress Enter to leave watch mode.");
+                    while !matches!(rx.recv(), Ok(WatchEvent::Stop) | Err(_)) {}
+                    break;
+                }
+            };
+            self.display_description_and_instructions();
+            self.print_watch_banner();
+        }
+
+        let _ = reader.join();
+        println!("👋 Left watch mode.");
+    }
+
+    fn print_watch_banner(&self) {
+        let chapter = &self.chapters[self.current_chapter];
+        let exercise = &chapter.exercises[self.current_exercise];
+        println!("\n👀 Watching exercise {}.{}: {}", self.current_chapter + 1, self.current_exercise + 1, exercise.title);
+        let dir = match &exercise.cargo {
+            Some(_) => self.workspace.project_dir().join(crate::project::SOURCE_DIR),
+            None => self.workspace.path().to_path_buf(),
+        };
+        if self.current_files.is_empty() {
+            println!("📝 Edit {} in your editor; every save re-runs it.", dir.join("main.rs").display());
+        } else {
+            println!("📝 Edit the files in {} in your editor; every save re-runs them.", dir.display());
+        }
+        println!("⏎  Press Enter to stop watching.");
+    }
+}
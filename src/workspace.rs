@@ -0,0 +1,94 @@
+// Every session compiles and edits inside its own private directory under
+// the system temp dir, so concurrent tutors never clobber each other and
+// nothing is left behind in the directory the tutor was launched from.
+
+use std::env;
+use std::fs::{self, DirBuilder};
+use std::io;
+use std::os::unix::fs::DirBuilderExt;
+use std::path::{Path, PathBuf};
+use std::process;
+
+pub struct Workspace {
+    dir: PathBuf,
+}
+
+impl Workspace {
+    pub fn create() -> io::Result<Self> {
+        let base = env::temp_dir();
+        let mut attempt = 0;
+        loop {
+            let dir = base.join(format!("rusttutor-{}-{}", process::id(), attempt));
+            match DirBuilder::new().mode(0o700).create(&dir) {
+                Ok(()) => return Ok(Self { dir }),
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists && attempt < 100 => attempt += 1,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn source_file(&self) -> PathBuf {
+        self.dir.join("main.rs")
+    }
+
+    // Cargo-project exercises are laid out and built here
+    pub fn project_dir(&self) -> PathBuf {
+        self.dir.join("project")
+    }
+
+    pub fn binary(&self) -> PathBuf {
+        self.dir.join("main")
+    }
+
+    // Learner code plus hidden tests; kept apart from `source_file` so the
+    // tests never show up in the editor or trigger watch mode.
+    pub fn test_source_file(&self) -> PathBuf {
+        self.dir.join("main_tests.rs")
+    }
+
+    pub fn test_binary(&self) -> PathBuf {
+        self.dir.join("main_tests")
+    }
+
+    // Where the full-screen UI collects what the tutor prints
+    pub fn output_file(&self) -> PathBuf {
+        self.dir.join("output.txt")
+    }
+}
+
+// Deletes the Rust files under `dir` other than those in `keep` (relative
+// to `dir`), so modules left by an earlier exercise can't be picked up by
+// this one's `mod` declarations. `skip` is a directory to leave alone.
+pub fn remove_stale_sources(dir: &Path, keep: &[PathBuf], skip: Option<&Path>) -> io::Result<()> {
+    remove_stale(dir, dir, keep, skip)
+}
+
+fn remove_stale(root: &Path, dir: &Path, keep: &[PathBuf], skip: Option<&Path>) -> io::Result<()> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e),
+    };
+    for entry in entries {
+        let path = entry?.path();
+        if Some(path.as_path()) == skip {
+            continue;
+        }
+        if path.is_dir() {
+            remove_stale(root, &path, keep, skip)?;
+        } else if path.extension().is_some_and(|ext| ext == "rs") && !keep.iter().any(|name| root.join(name) == path) {
+            fs::remove_file(&path)?;
+        }
+    }
+    Ok(())
+}
+
+impl Drop for Workspace {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
@@ -0,0 +1,62 @@
+// Runs `selftest` on the built-in exercises, so a broken solution fails
+// `cargo test`.
+
+use std::env;
+use std::fs;
+use std::process::Command;
+
+#[test]
+fn built_in_solutions_pass() {
+    // A data directory of our own: no saved progress, no installed content pack
+    let data_dir = env::temp_dir().join(format!("rusttutor-selftest-{}", std::process::id()));
+    let output = Command::new(env!("CARGO_BIN_EXE_claude-rust-by-example-app"))
+        .arg("selftest")
+        .env("XDG_DATA_HOME", &data_dir)
+        .env("RUSTTUTOR_CONTENT", data_dir.join("no-content"))
+        .env("NO_COLOR", "1")
+        .output()
+        .expect("could not run the tutor");
+    let _ = fs::remove_dir_all(&data_dir);
+
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stdout));
+}
+
+// Two exercises in a row whose modules share a name but not a layout:
+// the first one's files must be gone before the second one is built, or
+// `mod shapes` finds both shapes.rs and shapes/mod.rs (E0761), and the
+// Cargo project also builds the first exercise's lib.rs.
+#[test]
+fn multi_file_exercises_in_a_row() {
+    let data_dir = env::temp_dir().join(format!("rusttutor-multifile-{}", std::process::id()));
+    let chapter = data_dir.join("content").join("01-modules");
+    fs::create_dir_all(&chapter).unwrap();
+    fs::write(chapter.join("chapter.toml"), "title = \"Modules\"\ndescription = \"Modules in files.\"\n").unwrap();
+
+    let exercise = |title: &str, cargo: bool, files: &str| {
+        let cargo = if cargo { "[cargo]\nmanifest = \"\"\"\n[package]\nname = \"shapes\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\"\"\"\n" } else { "" };
+        format!(
+            "title = \"{}\"\ndescription = \"d\"\ninitial_code = \"\"\ninstructions = []\nexpected_output = \"4\"\nsolution = \"\"\"\nmod shapes;\n\nfn main() {{\n    println!(\"{{}}\", shapes::area());\n}}\n\"\"\"\n{}\n[files]\n{}",
+            title, cargo, files
+        )
+    };
+    let flat = "\"shapes.rs\" = \"pub fn area() -> u32 { 4 }\\n\"\n";
+    let nested = "\"shapes/mod.rs\" = \"mod square;\\npub use self::square::area;\\n\"\n\"shapes/square.rs\" = \"pub fn area() -> u32 { 2 * 2 }\\n\"\n";
+    let with_lib = "\"lib.rs\" = \"pub mod shapes;\\n\"\n\"shapes.rs\" = \"pub fn area() -> u32 { 4 }\\n\"\n";
+    fs::write(chapter.join("01-flat.toml"), exercise("Flat", false, flat)).unwrap();
+    fs::write(chapter.join("02-nested.toml"), exercise("Nested", false, nested)).unwrap();
+    fs::write(chapter.join("03-cargo-lib.toml"), exercise("Cargo with lib.rs", true, with_lib)).unwrap();
+    fs::write(chapter.join("04-cargo-nested.toml"), exercise("Cargo nested", true, nested)).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_claude-rust-by-example-app"))
+        .arg("selftest")
+        .env("XDG_DATA_HOME", &data_dir)
+        .env("RUSTTUTOR_CONTENT", data_dir.join("content"))
+        .env("NO_COLOR", "1")
+        .output()
+        .expect("could not run the tutor");
+    let _ = fs::remove_dir_all(&data_dir);
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("checking 4 exercises"), "{}", stdout);
+    assert!(output.status.success(), "{}", stdout);
+}